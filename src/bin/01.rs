@@ -1,112 +1,133 @@
-advent_of_code::solution!(1);
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let value_mapping = make_value_mapping_part_1();
-
-    let mut sum_of_values = 0;
-
-    for line in input.lines() {
-        let digits: Vec<u32> = recover_digits(line, &value_mapping);
-
-        match recover_calibration_value(&digits) {
-            Some(value) => {
-                // println!("{} -> {:?}: {}", line_string, digits, value);
+use std::error::Error;
+use std::fmt::Display;
+
+use advent_of_code::matcher::Matcher;
+
+advent_of_code::solution!(1, commands: ["--missing-digits" => missing_digits]);
+
+/// Words that are recovered as a digit, e.g. `("seven", 7)`.
+type Vocabulary<'a> = &'a [(&'a str, u32)];
+
+const DIGITS: Vocabulary = &[
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
 
-                sum_of_values += value;
-            }
-            None => {
-                println!("{} -> {:?}: failed to recover value", &line, digits);
-            }
-        }
-    }
+const ENGLISH: Vocabulary = &[
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
 
-    Some(sum_of_values)
+pub fn part_one(input: &str) -> Option<u32> {
+    Some(calibrate(input, &matcher(&[DIGITS])).sum())
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let value_mapping = make_value_mapping_part_2();
+    Some(calibrate(input, &matcher(&[ENGLISH, DIGITS])).sum())
+}
 
-    let mut sum_of_values = 0;
+fn matcher(vocabularies: &[Vocabulary]) -> Matcher<u32> {
+    Matcher::new(
+        vocabularies
+            .iter()
+            .flat_map(|vocabulary| vocabulary.iter().copied()),
+    )
+}
 
-    for line in input.lines() {
-        let digits: Vec<u32> = recover_digits(line, &value_mapping);
+/// A line of the calibration document that does not contain any digit.
+#[derive(Debug, PartialEq, Eq)]
+struct MissingDigit {
+    line: usize,
+    text: String,
+}
 
-        match recover_calibration_value(&digits) {
-            Some(value) => {
-                sum_of_values += value;
-            }
-            None => {
-                println!("{} -> {:?}: failed to recover value", &line, digits);
-            }
-        }
+impl Display for MissingDigit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: no digit in {:?}", self.line, self.text)
     }
-
-    Some(sum_of_values)
 }
 
-fn make_value_mapping_part_1() -> Vec<(String, u32)> {
-    let digit_value_mapping: Vec<(String, u32)> = (1..=9)
-        .map(|v| (v.to_string().to_owned(), v as u32))
-        .collect();
-
-    digit_value_mapping
+#[derive(Debug, Default)]
+struct Calibration {
+    values: Vec<u32>,
+    missing: Vec<MissingDigit>,
 }
 
-const NUMBERS: [&str; 9] = [
-    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-];
-
-fn make_value_mapping_part_2() -> Vec<(String, u32)> {
-    let mut word_value_mapping: Vec<(String, u32)> = NUMBERS
-        .iter()
-        .enumerate()
-        .map(|(index, &item)| (item.to_owned(), (index + 1) as u32))
-        .collect();
-
-    let mut digit_value_mapping: Vec<(String, u32)> = (1..=9)
-        .map(|v| (v.to_string().to_owned(), v as u32))
-        .collect();
+impl Calibration {
+    /// The sum of all values, lines without a digit are left out.
+    fn sum(&self) -> u32 {
+        self.values.iter().sum()
+    }
+}
 
-    assert_eq!(
-        digit_value_mapping.len(),
-        9,
-        "There should be nine digits, but digit_value_mapping={:?}",
-        digit_value_mapping
-    );
+fn calibrate(input: &str, matcher: &Matcher<u32>) -> Calibration {
+    let mut calibration = Calibration::default();
 
-    word_value_mapping.append(&mut digit_value_mapping);
+    for (i, line) in input.lines().enumerate() {
+        match calibration_value(line, matcher) {
+            Some(value) => calibration.values.push(value),
+            None => calibration.missing.push(MissingDigit {
+                line: i + 1,
+                text: line.to_string(),
+            }),
+        }
+    }
 
-    word_value_mapping
+    calibration
 }
 
-fn recover_digits(line: &str, value_mapping: &Vec<(String, u32)>) -> Vec<u32> {
-    let mut found: Vec<(usize, u32)> = vec![];
+/// Prints the lines without a digit for both parts.
+pub fn missing_digits(input: &str, _args: &[String]) -> Result<(), Box<dyn Error>> {
+    let parts = [(1, matcher(&[DIGITS])), (2, matcher(&[ENGLISH, DIGITS]))];
 
-    for (pattern, value) in value_mapping {
-        let mut matches: Vec<(usize, u32)> = line
-            .match_indices(pattern)
-            .map(|(i, _)| (i, value.to_owned()))
-            .collect();
+    for (part, matcher) in parts {
+        let calibration = calibrate(input, &matcher);
+        println!(
+            "Part {part}: {} lines without a digit",
+            calibration.missing.len()
+        );
 
-        found.append(&mut matches);
+        for missing in &calibration.missing {
+            println!("  {missing}");
+        }
     }
 
-    found.sort_by(|(a, _), (b, _)| a.cmp(b));
-
-    found.iter().map(|(_, v)| v.to_owned()).collect()
+    Ok(())
 }
 
-fn recover_calibration_value(digits: &Vec<u32>) -> Option<u32> {
-    return match (digits.first(), digits.last()) {
-        (Some(first), Some(last)) => Some(10 * first + last),
-        _ => None,
-    };
+/// The first and the last digit of the line, combined into a two-digit number.
+fn calibration_value(line: &str, matcher: &Matcher<u32>) -> Option<u32> {
+    let (first, last) = matcher.first_and_last(line)?;
+
+    Some(10 * first.value + last.value)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Every digit of the line, in order.
+    fn recover_digits(line: &str, matcher: &Matcher<u32>) -> Vec<u32> {
+        let mut found: Vec<_> = matcher.find_iter(line).collect();
+        found.sort_by_key(|m| m.start);
+
+        found.into_iter().map(|m| *m.value).collect()
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -126,7 +147,7 @@ mod tests {
         let input = "2fivejrnpfbfive3grhdcngfvkxqrl5";
 
         assert_eq!(
-            recover_digits(&input.to_owned(), &make_value_mapping_part_2()),
+            recover_digits(input, &matcher(&[ENGLISH, DIGITS])),
             vec![2, 5, 5, 3, 5]
         );
     }
@@ -136,18 +157,57 @@ mod tests {
         let input = "zero2fivejrnpfbfive3grhdcngfvkxqrl5";
 
         assert_eq!(
-            recover_digits(&input.to_owned(), &make_value_mapping_part_2()),
+            recover_digits(input, &matcher(&[ENGLISH, DIGITS])),
             vec![2, 5, 5, 3, 5]
         );
     }
 
     #[test]
     fn test_spelled_digits_into_digits_replaced_first_occurence() {
-        let input = "eightwothree".to_owned();
+        let input = "eightwothree";
 
         assert_eq!(
-            recover_digits(&input.to_owned(), &make_value_mapping_part_2()),
+            recover_digits(input, &matcher(&[ENGLISH, DIGITS])),
             vec![8, 2, 3]
         );
     }
+
+    #[test]
+    fn test_overlapping_last_digit() {
+        assert_eq!(
+            calibration_value("4nineeightwo", &matcher(&[ENGLISH, DIGITS])),
+            Some(42)
+        );
+        assert_eq!(calibration_value("oneight", &matcher(&[ENGLISH])), Some(18));
+    }
+
+    #[test]
+    fn test_other_vocabularies() {
+        const ZERO: Vocabulary = &[("zero", 0)];
+        const GERMAN: Vocabulary = &[("eins", 1), ("zwei", 2), ("drei", 3), ("neun", 9)];
+
+        let with_zero = matcher(&[ZERO, ENGLISH, DIGITS]);
+        assert_eq!(calibration_value("zero2five", &with_zero), Some(5));
+
+        let german = matcher(&[GERMAN, DIGITS]);
+        assert_eq!(calibration_value("xzweiundneunzigdrei", &german), Some(23));
+    }
+
+    #[test]
+    fn test_missing_digits_are_reported() {
+        let calibration = calibrate("a1b\nnope\n7\n", &matcher(&[DIGITS]));
+
+        assert_eq!(calibration.values, vec![11, 77]);
+        assert_eq!(
+            calibration.missing,
+            vec![MissingDigit {
+                line: 2,
+                text: "nope".to_string()
+            }]
+        );
+        assert_eq!(
+            calibration.missing[0].to_string(),
+            "line 2: no digit in \"nope\""
+        );
+    }
 }
@@ -1,101 +1,130 @@
-advent_of_code::solution!(2);
+advent_of_code::solution!(2, commands: ["--bag" => with_bag]);
 
+use advent_of_code::scan;
+use advent_of_code::scan::ScanError;
 use std::{
-    collections::HashMap,
+    collections::BTreeMap,
+    error::Error,
     fmt::{self},
     str::FromStr,
 };
 
-// type Map<T1, T2> = BTreeMap<Vec<T1>, T2>
+/// The bag of the puzzle: 12 red cubes, 13 green cubes, and 14 blue cubes.
+const PUZZLE_BAG: [(&str, u32); 3] = [("red", 12), ("green", 13), ("blue", 14)];
 
-fn verify_game(game: &Game) -> bool {
-    let constraints = [("red", 12), ("green", 13), ("blue", 14)];
+/// The amount of cubes of each colour in a bag. Colours that are not listed are not in the bag.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Bag(BTreeMap<String, u32>);
 
-    for set in &game.records {
-        for (item, cnt) in set {
-            for (constraint_item, max) in constraints {
-                if item == constraint_item {
-                    if cnt > &max {
-                        return false;
-                    }
-                }
-            }
-        }
+impl Bag {
+    pub fn count(&self, colour: &str) -> u32 {
+        self.0.get(colour).copied().unwrap_or(0)
     }
 
-    return true;
-}
+    /// Whether every draw of the game could have been taken from this bag.
+    /// A colour the bag does not list counts as zero cubes, so a game that draws it is impossible.
+    pub fn allows(&self, game: &Game) -> bool {
+        game.records
+            .iter()
+            .flatten()
+            .all(|(colour, count)| *count <= self.count(colour))
+    }
 
-fn power_of_game(game: &Game) -> u32 {
-    // let minimums: HashMap<&str, u32> = vec![("red", 0), ("green", 0), ("blue", 0)].into_iter().collect();
-    let mut minimums: HashMap<&str, u32> = HashMap::new();
+    /// The product of the cube counts of all colours in the bag.
+    pub fn power(&self) -> u32 {
+        self.0.values().product()
+    }
+}
 
-    for set in &game.records {
-        for (item, cnt) in set {
-            let entry = minimums.entry(item).or_insert(0);
-            *entry = std::cmp::max(*entry, *cnt);
-        }
+impl<'a> FromIterator<(&'a str, u32)> for Bag {
+    fn from_iter<I: IntoIterator<Item = (&'a str, u32)>>(iter: I) -> Self {
+        Bag(iter
+            .into_iter()
+            .map(|(colour, count)| (colour.to_string(), count))
+            .collect())
     }
+}
 
-    return minimums.into_values().product();
+/// Parses the cubes of a bag like a draw, e.g. `12 red, 13 green, 14 blue`.
+impl FromStr for Bag {
+    type Err = ParseGameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Bag(parse_set(s)?.into_iter().collect()))
+    }
 }
 
 #[derive(Debug, PartialEq)]
-struct Game {
+pub struct Game {
     id: u32,
     records: Vec<Vec<(String, u32)>>,
 }
 
-#[derive(Debug)]
-pub enum MyCustomError {
-    ParseError,
-    ParseIntError,
-}
+impl Game {
+    /// The smallest bag that allows every draw of the game.
+    pub fn minimal_bag(&self) -> Bag {
+        let mut minimums: BTreeMap<String, u32> = BTreeMap::new();
+
+        for (colour, count) in self.records.iter().flatten() {
+            let entry = minimums.entry(colour.clone()).or_insert(0);
+            *entry = (*entry).max(*count);
+        }
 
-impl From<std::num::ParseIntError> for MyCustomError {
-    fn from(_value: std::num::ParseIntError) -> Self {
-        MyCustomError::ParseIntError
+        Bag(minimums)
     }
 }
 
-impl std::error::Error for MyCustomError {}
+/// A line that is not a game, naming the game (when its header could be read) and the token at fault.
+#[derive(Debug, PartialEq)]
+pub struct ParseGameError {
+    game: Option<u32>,
+    token: String,
+    source: ScanError,
+}
 
-impl fmt::Display for MyCustomError {
+impl std::error::Error for ParseGameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl fmt::Display for ParseGameError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            MyCustomError::ParseError => write!(f, "Parse Error"),
-            MyCustomError::ParseIntError => write!(f, "Parse Int Error"),
+        if let Some(game) = self.game {
+            write!(f, "game {game}: ")?;
         }
+
+        write!(f, "invalid {:?}, {}", self.token, self.source)
+    }
+}
+
+/// Wraps an error of scanning `token`, the game is filled in once it is known.
+fn invalid(token: &str) -> impl FnOnce(ScanError) -> ParseGameError + '_ {
+    move |source| ParseGameError {
+        game: None,
+        token: token.to_string(),
+        source,
     }
 }
 
 impl FromStr for Game {
-    // type Err = std::num::ParseIntError;
-    type Err = MyCustomError;
+    type Err = ParseGameError;
 
-    // Parses a color hex code of the form '#rRgGbB..' into an
-    // instance of 'RGB'
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let colon_location = line.find(":").ok_or_else(|| MyCustomError::ParseError)?;
-
-        let game_id_string = &line[5..colon_location];
-
-        let game_id = game_id_string.parse::<u32>()?;
-
-        let sets_string = &line[colon_location + 1..];
-
-        let mut records = vec![];
-
-        for set_description in sets_string.split(";") {
-            let set = parse_set(set_description)?;
-
-            records.push(set);
-        }
-
-        Ok(Game {
-            id: game_id,
-            records: records,
-        })
+        let (id, set_descriptions) =
+            scan!(line, "Game {}: {}" => (&str, &str)).map_err(invalid(line))?;
+        let (id,) = scan!(id, "{}" => (u32,)).map_err(invalid(id))?;
+
+        let records = set_descriptions
+            .split(';')
+            .map(parse_set)
+            .collect::<Result<_, _>>()
+            .map_err(|e| ParseGameError {
+                game: Some(id),
+                ..e
+            })?;
+
+        Ok(Game { id, records })
     }
 }
 
@@ -105,60 +134,118 @@ impl fmt::Display for Game {
     }
 }
 
-fn parse_set(set_description: &str) -> Result<Vec<(String, u32)>, MyCustomError> {
-    // dbg!(set_description);
+fn parse_set(set_description: &str) -> Result<Vec<(String, u32)>, ParseGameError> {
+    set_description
+        .split(',')
+        .map(str::trim)
+        .map(|cubes| {
+            let (count, colour) = scan!(cubes, "{} {}" => (u32, String)).map_err(invalid(cubes))?;
 
-    let mut items = vec![];
+            Ok((colour, count))
+        })
+        .collect()
+}
 
-    for item in set_description.split(",") {
-        let item = item.trim();
+pub fn parse_games(input: &str) -> Result<Vec<Game>, ParseGameError> {
+    input.lines().map(|line| line.parse()).collect()
+}
 
-        let count_and_item: Vec<&str> = item.split(" ").collect();
+/// The sum of the ids of the games that are possible with `bag`.
+pub fn sum_of_possible_ids(games: &[Game], bag: &Bag) -> u32 {
+    games
+        .iter()
+        .filter(|game| bag.allows(game))
+        .map(|game| game.id)
+        .sum()
+}
 
-        // dbg!(&count_and_item);
+/// How the possible games change when the limit for `colour` is raised, keeping the other limits of `bag`.
+/// Returns each limit at which games become possible, in increasing order, with the ids of those games.
+pub fn possible_as_limit_rises(games: &[Game], bag: &Bag, colour: &str) -> Vec<(u32, Vec<u32>)> {
+    let mut thresholds: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
 
-        let count = count_and_item[0].parse::<u32>()?;
-        let item = count_and_item[1];
+    for game in games {
+        let minimal = game.minimal_bag();
 
-        // dbg!(count);
-        // dbg!(item);
+        let others_fit = minimal
+            .0
+            .iter()
+            .all(|(other, &count)| other == colour || count <= bag.count(other));
 
-        items.push((item.to_owned(), count));
+        if others_fit {
+            thresholds
+                .entry(minimal.count(colour))
+                .or_default()
+                .push(game.id);
+        }
     }
 
-    return Ok(items);
+    thresholds.into_iter().collect()
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut verified_ids: Vec<u32> = vec![];
-
-    for line in input.lines() {
-        match line.parse::<Game>() {
-            Ok(game) => {
-                if verify_game(&game) {
-                    verified_ids.push(game.id)
-                }
-            }
-            Err(e) => println!("Failed to parse: {}", e),
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ColourStats {
+    /// Games in which the colour was drawn at least once.
+    pub games: usize,
+    /// Draws that contained the colour.
+    pub draws: usize,
+    /// Cubes of the colour over all draws.
+    pub total: u32,
+    /// The most cubes of the colour in a single draw.
+    pub max: u32,
+}
+
+pub fn colour_stats(games: &[Game]) -> BTreeMap<String, ColourStats> {
+    let mut stats: BTreeMap<String, ColourStats> = BTreeMap::new();
+
+    for game in games {
+        for (colour, _) in game.minimal_bag().0 {
+            stats.entry(colour).or_default().games += 1;
+        }
+
+        for (colour, count) in game.records.iter().flatten() {
+            let entry = stats.entry(colour.clone()).or_default();
+            entry.draws += 1;
+            entry.total += count;
+            entry.max = entry.max.max(*count);
         }
     }
 
-    Some(verified_ids.iter().sum())
+    stats
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut sum_of_powers = 0;
+/// Part one for any bag.
+pub fn part_one_with_bag(input: &str, bag: &Bag) -> Option<u32> {
+    let games = parse_games(input)
+        .inspect_err(|e| eprintln!("invalid game: {e}"))
+        .ok()?;
 
-    for line in input.lines() {
-        match line.parse::<Game>() {
-            Ok(game) => {
-                sum_of_powers += power_of_game(&game);
-            }
-            Err(e) => println!("Failed to parse: {}", e),
-        }
-    }
+    Some(sum_of_possible_ids(&games, bag))
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    part_one_with_bag(input, &PUZZLE_BAG.into_iter().collect())
+}
+
+/// Runs part one with the bag given as the first argument, e.g. `--bag "12 red, 13 green, 2 purple"`.
+pub fn with_bag(input: &str, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let bag: Bag = args
+        .first()
+        .ok_or("--bag expects the cubes of the bag, e.g. \"12 red, 13 green\"")?
+        .parse()?;
+    let games = parse_games(input)?;
+
+    println!("Part 1: {}", sum_of_possible_ids(&games, &bag));
 
-    Some(sum_of_powers)
+    Ok(())
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    let games = parse_games(input)
+        .inspect_err(|e| eprintln!("invalid game: {e}"))
+        .ok()?;
+
+    Some(games.iter().map(|game| game.minimal_bag().power()).sum())
 }
 
 #[cfg(test)]
@@ -225,4 +312,82 @@ mod tests {
 
         assert_eq!(set, expected);
     }
+
+    #[test]
+    fn test_parse_errors_name_game_and_token() {
+        let e = Game::from_str("Game 7: 3 blue; x red, 2 green").unwrap_err();
+        assert_eq!((e.game, e.token.as_str()), (Some(7), "x red"));
+        assert!(e.to_string().starts_with("game 7: invalid \"x red\""));
+
+        let e = Game::from_str("Game seven: 3 blue").unwrap_err();
+        assert_eq!((e.game, e.token.as_str()), (None, "seven"));
+
+        let e = Game::from_str("Round 1: 3 blue").unwrap_err();
+        assert_eq!(e.game, None);
+    }
+
+    #[test]
+    fn test_custom_bag_and_colours() {
+        let games = parse_games("Game 1: 2 purple, 1 red\nGame 2: 5 red\n").unwrap();
+
+        let bag: Bag = [("red", 5)].into_iter().collect();
+        assert_eq!(sum_of_possible_ids(&games, &bag), 2);
+
+        let bag: Bag = [("red", 5), ("purple", 2)].into_iter().collect();
+        assert_eq!(sum_of_possible_ids(&games, &bag), 3);
+        assert_eq!(games[0].minimal_bag().power(), 2);
+    }
+
+    #[test]
+    fn test_colour_missing_from_bag() {
+        let game = Game::from_str("Game 1: 1 purple; 2 red").unwrap();
+
+        let bag: Bag = "5 red, 3 blue".parse().unwrap();
+        assert!(!bag.allows(&game));
+
+        let bag: Bag = "5 red, 1 purple".parse().unwrap();
+        assert!(bag.allows(&game));
+    }
+
+    #[test]
+    fn test_malformed_line() {
+        let input = "Game 1: 2 red\nGame 2: many red\n";
+
+        let e = parse_games(input).unwrap_err();
+        assert_eq!((e.game, e.token.as_str()), (Some(2), "many red"));
+
+        assert_eq!(part_one(input), None);
+        assert_eq!(part_two(input), None);
+    }
+
+    #[test]
+    fn test_possible_as_limit_rises() {
+        let games = parse_games(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+
+        let bag: Bag = PUZZLE_BAG.into_iter().collect();
+
+        // Game 4 needs 15 blue cubes, no amount of red ones makes it possible.
+        assert_eq!(
+            possible_as_limit_rises(&games, &bag, "red"),
+            vec![(1, vec![2]), (4, vec![1]), (6, vec![5]), (20, vec![3])]
+        );
+    }
+
+    #[test]
+    fn test_colour_stats() {
+        let games = parse_games(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+
+        let stats = colour_stats(&games);
+
+        assert_eq!(stats.len(), 3);
+        assert_eq!(
+            stats["green"],
+            ColourStats {
+                games: 5,
+                draws: 13,
+                total: 48,
+                max: 13
+            }
+        );
+    }
 }
@@ -1,95 +1,166 @@
-use std::cmp::{max, min};
+use std::ops::Range;
 
-use regex::Regex;
+use advent_of_code::grid::{Grid, Point};
 
 advent_of_code::solution!(3);
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let lines = input.lines().collect();
-
-    let parts = find_parts(&lines);
-
-    let found_numbers = parts.into_iter().map(|(value, _)| value);
-
-    Some(found_numbers.into_iter().sum())
+/// A number in the schematic, spanning `columns` of `row`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub value: u32,
+    pub row: usize,
+    pub columns: Range<usize>,
 }
 
-fn find_parts(lines: &Vec<&str>) -> Vec<(u32, (i32, (i32, i32)))> {
-    let find_numbers = Regex::new(r"\d+").expect("Invalid regex");
-
-    let mut parts: Vec<(u32, (i32, (i32, i32)))> = Vec::new();
-
-    for (i, line) in lines.iter().enumerate() {
-        let i = i as i32;
-        for (j_start, substring) in line.match_indices(&find_numbers) {
-            let j_end = j_start + substring.chars().count();
+/// Any character that is not a digit, a `.` or blank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub ch: char,
+    pub location: Point,
+}
 
-            let scan_start: usize = max(0, (j_start as i32) - 1) as usize;
-            let scan_end: usize = min(line.chars().count(), j_end + 1);
+/// The engine schematic, parsed once into its numbers and symbols.
+/// Lines may have different lengths, columns count characters.
+pub struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    /// The index in `numbers` of the number covering each cell.
+    number_at: Grid<Option<usize>>,
+}
 
-            let mut is_part = false;
+impl Schematic {
+    pub fn parse(input: &str) -> Self {
+        let width = input
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let height = input.lines().count();
+
+        let mut numbers: Vec<Number> = vec![];
+        let mut symbols = vec![];
+        let mut number_at = Grid::filled(width, height, None);
+
+        for (row, line) in input.lines().enumerate() {
+            let mut current: Option<Number> = None;
+
+            for (column, ch) in line.chars().chain(['.']).enumerate() {
+                if let Some(digit) = ch.to_digit(10) {
+                    let number = current.get_or_insert(Number {
+                        value: 0,
+                        row,
+                        columns: column..column,
+                    });
+                    number.value = number.value * 10 + digit;
+                    number.columns.end = column + 1;
+                    number_at[(column, row)] = Some(numbers.len());
+                    continue;
+                }
 
-            let search_range = ((i - 1)..=(i + 1)).filter(|i| i >= &0).map(|i| i as usize);
+                numbers.extend(current.take());
 
-            for neighbor_i in search_range {
-                if let Some(neighbor_line) = lines.get(neighbor_i) {
-                    if neighbor_line[scan_start..scan_end]
-                        .contains(|c: char| !(c.is_numeric() || c == '.'))
-                    {
-                        is_part = true;
-                    }
+                if ch != '.' && !ch.is_whitespace() {
+                    symbols.push(Symbol {
+                        ch,
+                        location: (column, row),
+                    });
                 }
             }
+        }
 
-            if is_part {
-                let value: u32 = substring.parse().expect("Expected to parse the number");
-
-                parts.push((value, (i as i32, (j_start as i32, j_end as i32))));
-            }
+        Self {
+            numbers,
+            symbols,
+            number_at,
         }
     }
-    parts
-}
-
-pub fn part_two(input: &str) -> Option<u32> {
-    let lines: Vec<&str> = input.lines().collect();
 
-    let parts = find_parts(&lines);
-
-    let mut gear_ratios = Vec::new();
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
 
-    for (star_i, line) in lines.iter().enumerate() {
-        let star_i = star_i as i32;
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
 
-        for (star_j, _) in line.match_indices('*') {
-            let star_j = star_j as i32;
-            // let search_range = ((i-1)..=(i+1)).filter(|i| i >= &0).map(|i| i as usize);
+    /// The indices of the numbers that touch `location`, diagonals included.
+    fn adjacent(&self, location: Point) -> Vec<usize> {
+        let mut adjacent: Vec<usize> = self
+            .number_at
+            .neighbors8(location)
+            .filter_map(|p| self.number_at[p])
+            .collect();
+
+        adjacent.sort_unstable();
+        adjacent.dedup();
+        adjacent
+    }
 
-            let mut star_values = Vec::new();
+    /// The numbers that touch `symbol`.
+    pub fn parts_of(&self, symbol: &Symbol) -> Vec<&Number> {
+        self.adjacent(symbol.location)
+            .into_iter()
+            .map(|i| &self.numbers[i])
+            .collect()
+    }
 
-            // println!("checking star: {},{}", star_i, star_j);
+    /// Whether each number touches a symbol.
+    fn attached(&self) -> Vec<bool> {
+        let mut attached = vec![false; self.numbers.len()];
 
-            for (part_value, (part_i, (part_j_start, part_j_end))) in &parts {
-                if star_i + 1 >= *part_i && star_i - 1 <= *part_i {
-                    if star_j >= *part_j_start - 1 && star_j <= *part_j_end {
-                        star_values.push(part_value);
-                    }
-                }
+        for symbol in &self.symbols {
+            for i in self.adjacent(symbol.location) {
+                attached[i] = true;
             }
+        }
 
-            // dbg!(&star_values);
+        attached
+    }
 
-            if star_values.len() == 2 {
-                let gear_ratio: u32 = star_values.iter().copied().product();
+    /// The numbers that touch at least one symbol.
+    pub fn parts(&self) -> Vec<&Number> {
+        self.numbers
+            .iter()
+            .zip(self.attached())
+            .filter_map(|(number, attached)| attached.then_some(number))
+            .collect()
+    }
 
-                gear_ratios.push(gear_ratio);
+    /// The numbers that touch no symbol.
+    pub fn unattached(&self) -> Vec<&Number> {
+        self.numbers
+            .iter()
+            .zip(self.attached())
+            .filter_map(|(number, attached)| (!attached).then_some(number))
+            .collect()
+    }
 
-                // println!("Found gear! {:?} -> {}", &star_values, gear_ratio);
-            }
-        }
+    /// The `ch` symbols that touch exactly `arity` numbers, together with those numbers.
+    pub fn gears(&self, ch: char, arity: usize) -> Vec<(Symbol, Vec<&Number>)> {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.ch == ch)
+            .map(|symbol| (*symbol, self.parts_of(symbol)))
+            .filter(|(_, parts)| parts.len() == arity)
+            .collect()
     }
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    let schematic = Schematic::parse(input);
 
-    Some(gear_ratios.into_iter().sum())
+    Some(schematic.parts().iter().map(|part| part.value).sum())
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    let schematic = Schematic::parse(input);
+
+    let gear_ratios = schematic
+        .gears('*', 2)
+        .into_iter()
+        .map(|(_, parts)| parts.iter().map(|part| part.value).product::<u32>());
+
+    Some(gear_ratios.sum())
 }
 
 #[cfg(test)]
@@ -108,4 +179,34 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(467835));
     }
+
+    fn values(numbers: &[&Number]) -> Vec<u32> {
+        numbers.iter().map(|number| number.value).collect()
+    }
+
+    #[test]
+    fn test_queries() {
+        let schematic = Schematic::parse(&advent_of_code::template::read_file("examples", DAY));
+
+        assert_eq!(schematic.numbers().len(), 10);
+        assert_eq!(schematic.symbols().len(), 6);
+        assert_eq!(values(&schematic.unattached()), vec![114, 58]);
+
+        let lonely_stars = schematic.gears('*', 1);
+        assert_eq!(lonely_stars.len(), 1);
+        assert_eq!(lonely_stars[0].0.location, (3, 4));
+        assert_eq!(values(&lonely_stars[0].1), vec![617]);
+
+        let hash = schematic.symbols().iter().find(|s| s.ch == '#').unwrap();
+        assert_eq!(values(&schematic.parts_of(hash)), vec![633]);
+    }
+
+    #[test]
+    fn test_ragged_lines() {
+        let schematic = Schematic::parse("12\n.é*..\n..3\n7");
+
+        assert_eq!(values(&schematic.parts()), vec![12, 3]);
+        assert_eq!(values(&schematic.unattached()), vec![7]);
+        assert_eq!(schematic.symbols()[1].location, (2, 1));
+    }
 }
@@ -1,70 +1,76 @@
-use std::{collections::HashSet, result};
+use std::error::Error;
 
-advent_of_code::solution!(4);
+use advent_of_code::bitset::BitSet;
+
+advent_of_code::solution!(4, commands: ["--copies" => report_copies]);
 
 pub fn part_one(input: &str) -> Option<u32> {
     let mut result = 0;
 
     for card in input.lines() {
-        let overlap = compute_winning_amount(&card);
+        let overlap = compute_winning_amount(card);
         if overlap >= 1 {
-            result += (2 as u32).pow(overlap as u32 - 1);
+            result += 2_u32.pow(overlap - 1);
         }
     }
 
     Some(result)
 }
 
-fn compute_winning_amount(card: &str) -> u32 {
-    let card = card.split(':').last().expect("invalid card");
-
-    let splitted: Vec<Vec<&str>> = card
-        .split("|")
-        .into_iter()
-        .map(|s| s.split_whitespace().collect())
-        .collect();
-
-    let my_numbers = splitted.first().expect("my numbers is None");
-    let winning_numbers = splitted.last().expect("winning numbers is None");
-
-    let mut my_numbers_set: HashSet<&str> = HashSet::new();
-    my_numbers_set.extend(my_numbers);
-
-    let mut winning_numbers_set: HashSet<&str> = HashSet::new();
-    winning_numbers_set.extend(winning_numbers);
+/// The numbers of one side of a card.
+fn numbers(numbers: &str) -> Vec<usize> {
+    numbers
+        .split_whitespace()
+        .map(|number| number.parse().expect("card numbers should be integers"))
+        .collect()
+}
 
-    let overlap = my_numbers_set.intersection(&winning_numbers_set).count();
+fn compute_winning_amount(card: &str) -> u32 {
+    let (_, card) = card.split_once(':').expect("card should contain ':'");
 
-    overlap as u32
-}
+    let (my_numbers, winning_numbers) = card.split_once('|').expect("card should contain '|'");
+    let winning_numbers = numbers(winning_numbers);
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let cards: Vec<&str> = input.lines().collect();
+    let capacity = winning_numbers.iter().max().map_or(0, |&max| max + 1);
+    let mut winning = BitSet::new(capacity);
+    for &number in &winning_numbers {
+        winning.insert(number);
+    }
 
-    let scores: Vec<u32> = cards
+    numbers(my_numbers)
         .into_iter()
-        .map(|card| compute_winning_amount(card))
-        .collect();
+        .filter(|&number| winning.contains(number))
+        .count() as u32
+}
 
-    let mut queue = Vec::new();
+/// The amount of each card you end up with, the original included.
+/// Each card only wins copies of later cards, so the amount of a card is final once it is reached.
+pub fn copies_per_card(input: &str) -> Vec<u32> {
+    let scores: Vec<u32> = input.lines().map(compute_winning_amount).collect();
 
-    queue.extend(0..scores.len());
+    let mut copies = vec![1; scores.len()];
 
-    let mut result = 0;
+    for (card_index, &score) in scores.iter().enumerate() {
+        let won = card_index + 1..(card_index + 1 + score as usize).min(scores.len());
 
-    while let Some(card_index) = queue.pop() {
-        let score = scores[card_index];
+        for new_index in won {
+            copies[new_index] += copies[card_index];
+        }
+    }
 
-        result += 1;
+    copies
+}
 
-        for new_index in (card_index + 1)..=(card_index + score as usize) {
-            if new_index < scores.len() {
-                queue.push(new_index);
-            }
-        }
+pub fn report_copies(input: &str, _args: &[String]) -> Result<(), Box<dyn Error>> {
+    for (card_index, copies) in copies_per_card(input).into_iter().enumerate() {
+        println!("Card {}: {copies}", card_index + 1);
     }
 
-    Some(result)
+    Ok(())
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    Some(copies_per_card(input).into_iter().sum())
 }
 
 #[cfg(test)]
@@ -82,4 +88,20 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(30));
     }
+
+    #[test]
+    fn test_copies_per_card() {
+        let result = copies_per_card(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, vec![1, 2, 4, 8, 14, 1]);
+    }
+
+    #[test]
+    fn test_winning_amount() {
+        assert_eq!(
+            compute_winning_amount("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"),
+            4
+        );
+        assert_eq!(compute_winning_amount("Card 2: 1 99 | 2 98"), 0);
+        assert_eq!(compute_winning_amount("Card 3: 128 7 1000 | 1000 5 128"), 2);
+    }
 }
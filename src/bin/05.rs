@@ -1,165 +1,147 @@
-use std::cmp::{max, min};
+use std::collections::BTreeMap;
 
-advent_of_code::solution!(5);
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let lines: Vec<&str> = input.lines().collect();
+use advent_of_code::interval::{IntervalSet, PiecewiseMap};
+use advent_of_code::parse::{self, ParseError, ParseErrorKind, Section};
+use advent_of_code::search::bfs;
 
-    let seeds = lines[0].split(":").last().expect("invalid seeds");
-    let seeds: Vec<u64> = seeds
-        .split_ascii_whitespace()
-        .map(|s| s.parse().expect("invalid seed u32s"))
-        .collect();
+advent_of_code::solution!(5);
 
-    dbg!(&seeds);
+/// The seeds and the maps between categories, e.g. `seed` to `soil`.
+/// The maps form a graph, so any category can be mapped to any category it leads to.
+#[derive(Debug, Clone)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    /// The maps by their source category, then by their destination category.
+    maps: BTreeMap<String, BTreeMap<String, PiecewiseMap<u64>>>,
+}
 
-    let mut mappings: Vec<Vec<(u64, u64, u64)>> = Vec::new();
+impl Almanac {
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let sections = parse::sections(input);
 
-    for line in &lines[1..] {
-        if line.contains("map:") {
-            mappings.push(Vec::new());
-        } else if line.contains(|c: char| c.is_ascii_digit()) {
-            let current_mapping = mappings
-                .last_mut()
-                .expect("expected to have already seen a map: string");
+        let (seeds, maps) = sections.split_first().ok_or(ParseError::new(
+            1,
+            1,
+            ParseErrorKind::Missing("seeds"),
+        ))?;
 
-            let numbers: Vec<u64> = line
-                .split_ascii_whitespace()
-                .map(|s| s.parse().expect("invalid u32"))
-                .collect();
+        let seeds = parse::labeled(seeds.text, "seeds:")
+            .and_then(parse::ints)
+            .map_err(|e| seeds.error(e))?;
 
-            assert!(numbers.len() == 3);
+        let mut almanac = Self {
+            seeds,
+            maps: BTreeMap::new(),
+        };
 
-            current_mapping.push((numbers[0], numbers[1], numbers[2]));
-        }
-    }
+        for section in maps {
+            let (source, destination) = parse_header(section)?;
 
-    let mut mapped_values = Vec::new();
+            let lines = section.body().lines(|line| match parse::ints(line)?[..] {
+                [dst, src, rl] => Ok((dst, src, rl)),
+                _ => Err(ParseError::new(1, 1, ParseErrorKind::Missing("3 numbers"))),
+            })?;
 
-    for ref seed in seeds {
-        let mut current_value: u64 = *seed;
+            let mut mapping = PiecewiseMap::new();
+            for (dst, src, rl) in lines {
+                mapping.insert(src..src + rl, dst);
+            }
 
-        for mapping in &mappings {
-            current_value = map_value(current_value, &mapping);
+            almanac
+                .maps
+                .entry(source.to_string())
+                .or_default()
+                .insert(destination.to_string(), mapping);
         }
 
-        mapped_values.push(current_value);
-    }
-
-    Some(mapped_values.into_iter().min().expect("failed to find min") as u32)
-}
-
-fn map_value(current_value: u64, mapping: &Vec<(u64, u64, u64)>) -> u64 {
-    for (dst, src, rl) in mapping {
-        if (*src..(*src + *rl)).contains(&current_value) {
-            return current_value - src + dst;
-        }
+        Ok(almanac)
     }
 
-    return current_value;
-}
-
-pub fn part_two(input: &str) -> Option<u32> {
-    let lines: Vec<&str> = input.lines().collect();
-
-    let parsed_seeds = lines[0].split(":").last().expect("invalid seeds");
-    let parsed_seeds: Vec<u64> = parsed_seeds
-        .split_ascii_whitespace()
-        .map(|s| s.parse().expect("invalid seed u32s"))
-        .collect();
-
-    let mut seed_ranges: Vec<(u64, u64)> = Vec::new();
-
-    for i in 0..(parsed_seeds.len() / 2) {
-        seed_ranges.push((parsed_seeds[i * 2], parsed_seeds[i * 2 + 1]));
+    /// All categories that appear in a map.
+    pub fn categories(&self) -> Vec<&str> {
+        let mut categories: Vec<&str> = self
+            .maps
+            .iter()
+            .flat_map(|(source, maps)| maps.keys().chain([source]))
+            .map(String::as_str)
+            .collect();
+
+        categories.sort_unstable();
+        categories.dedup();
+        categories
     }
 
-    let mut mappings: Vec<Vec<(u64, u64, u64)>> = Vec::new();
-
-    for line in &lines[1..] {
-        if line.contains("map:") {
-            mappings.push(Vec::new());
-        } else if line.contains(|c: char| c.is_ascii_digit()) {
-            let current_mapping = mappings
-                .last_mut()
-                .expect("expected to have already seen a map: string");
-
-            let numbers: Vec<u64> = line
-                .split_ascii_whitespace()
-                .map(|s| s.parse().expect("invalid u32"))
-                .collect();
+    /// The categories passed from `source` to `destination`, both included, using the fewest maps.
+    pub fn path(&self, source: &str, destination: &str) -> Option<Vec<&str>> {
+        let successors = |category: &&str| {
+            self.maps
+                .get(*category)
+                .into_iter()
+                .flat_map(BTreeMap::keys)
+                .map(String::as_str)
+        };
 
-            assert!(numbers.len() == 3);
+        let source = self.categories().into_iter().find(|&c| c == source)?;
 
-            current_mapping.push((numbers[0], numbers[1], numbers[2]));
-        }
+        bfs([source], successors, |&category| category == destination).map(|found| found.path)
     }
 
-    let mut mapped_values = Vec::new();
-
-    for ref seed in &seed_ranges {
-        let mut current_ranges: Vec<(u64, u64)> = vec![**seed];
+    /// A single map from `source` to `destination`, composed of the maps on the way.
+    pub fn mapping(&self, source: &str, destination: &str) -> Option<PiecewiseMap<u64>> {
+        let path = self.path(source, destination)?;
 
-        for mapping in &mappings {
-            current_ranges = map_ranges(current_ranges, &mapping);
-        }
+        let mapping = path
+            .windows(2)
+            .map(|step| &self.maps[step[0]][step[1]])
+            .fold(PiecewiseMap::new(), |mapping, next| mapping.then(next));
 
-        mapped_values.push(get_lowest_from_ranges(current_ranges));
+        Some(mapping)
     }
-
-    Some(mapped_values.into_iter().min().expect("failed to find min") as u32)
 }
 
-fn get_lowest_from_ranges(current_ranges: Vec<(u64, u64)>) -> u32 {
-    // dbg!(&current_ranges);
-
-    return current_ranges
-        .into_iter()
-        .min_by(|(x, _), (y, _)| x.cmp(y))
-        .expect("could not find min")
-        .0 as u32;
+/// The categories of a header like `seed-to-soil map:`.
+fn parse_header<'a>(section: &Section<'a>) -> Result<(&'a str, &'a str), ParseError> {
+    section
+        .header()
+        .strip_suffix(" map:")
+        .and_then(|name| name.split_once("-to-"))
+        .ok_or_else(|| {
+            section.error(ParseError::new(
+                1,
+                1,
+                ParseErrorKind::MissingLabel("<source>-to-<destination> map:".to_string()),
+            ))
+        })
 }
 
-fn map_ranges(current_ranges: Vec<(u64, u64)>, mapping: &[(u64, u64, u64)]) -> Vec<(u64, u64)> {
-    let mut queue = Vec::from_iter(current_ranges.clone());
-
-    let mut mapped_ranges = Vec::new();
-
-    while let Some((start, len)) = queue.pop() {
-        let mut found_at_least_one_mapping = false;
-
-        for (dst, src, rl) in mapping {
-            if start < src + rl && start + len > *src {
-                // defined as start, end ranges as [,) range
-                let range_before = (start, *src);
-                let range_overlap = (max(*src, start), min(*src + *rl, start + len));
-                let range_after = (*src + *rl, start + len);
-
-                if range_before.1 > range_before.0 {
-                    queue.push((range_before.0, range_before.1 - range_before.0));
-                }
+fn parse_almanac(input: &str) -> Almanac {
+    Almanac::parse(input).unwrap_or_else(|e| panic!("invalid almanac: {e}"))
+}
 
-                if range_after.1 > range_after.0 {
-                    queue.push((range_after.0, range_after.1 - range_after.0));
-                }
+fn seed_to_location(almanac: &Almanac) -> PiecewiseMap<u64> {
+    almanac
+        .mapping("seed", "location")
+        .expect("the almanac should map seeds to locations")
+}
 
-                assert!(range_overlap.1 > range_overlap.0);
+pub fn part_one(input: &str) -> Option<u64> {
+    let almanac = parse_almanac(input);
+    let mapping = seed_to_location(&almanac);
 
-                mapped_ranges.push((
-                    range_overlap.0 - *src + *dst,
-                    range_overlap.1 - range_overlap.0,
-                ));
+    almanac.seeds.iter().map(|&seed| mapping.apply(seed)).min()
+}
 
-                found_at_least_one_mapping = true;
-            }
-        }
+pub fn part_two(input: &str) -> Option<u64> {
+    let almanac = parse_almanac(input);
+    let mapping = seed_to_location(&almanac);
 
-        if found_at_least_one_mapping == false {
-            mapped_ranges.push((start, len));
-        }
-    }
+    let seed_ranges: IntervalSet<u64> = almanac
+        .seeds
+        .chunks_exact(2)
+        .map(|pair| pair[0]..pair[0] + pair[1])
+        .collect();
 
-    mapped_ranges
+    mapping.apply_set(&seed_ranges).min()
 }
 
 #[cfg(test)]
@@ -168,7 +150,7 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result: Option<u32> = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(35));
     }
 
@@ -177,4 +159,49 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(46));
     }
+
+    #[test]
+    fn test_any_categories() {
+        let almanac = parse_almanac(&advent_of_code::template::read_file("examples", DAY));
+
+        assert_eq!(
+            almanac.path("seed", "humidity").unwrap(),
+            vec![
+                "seed",
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity"
+            ]
+        );
+
+        let seed_to_humidity = almanac.mapping("seed", "humidity").unwrap();
+        let humidities: Vec<u64> = almanac
+            .seeds
+            .iter()
+            .map(|&s| seed_to_humidity.apply(s))
+            .collect();
+        assert_eq!(humidities, vec![78, 43, 82, 35]);
+
+        let light_to_location = almanac.mapping("light", "location").unwrap();
+        assert_eq!(light_to_location.apply(74), 82);
+
+        assert!(almanac.mapping("location", "seed").is_none());
+        assert!(almanac.mapping("seed", "unicorn").is_none());
+    }
+
+    #[test]
+    fn test_large_values() {
+        let input =
+            "seeds: 5000000000 5000000001\n\nseed-to-location map:\n4294967296 5000000000 2\n";
+        assert_eq!(part_one(input), Some(4_294_967_296));
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let error = Almanac::parse("seeds: 1\n\nseed to soil:\n1 2 3\n").unwrap_err();
+        assert_eq!(error.line, 3);
+    }
 }
@@ -1,79 +1,77 @@
 use std::iter::zip;
+use std::ops::RangeInclusive;
+
+use advent_of_code::math::isqrt;
+use advent_of_code::parse;
 
 advent_of_code::solution!(6);
 
-pub fn part_one(input: &str) -> Option<u32> {
+pub fn part_one(input: &str) -> Option<u64> {
     let lines: Vec<&str> = input.lines().collect();
 
-    let times: Vec<u64> = lines[0]
-        .split(":")
-        .last()
-        .expect("times : missing")
-        .split_whitespace()
-        .map(|s: &str| s.parse().expect("msg"))
-        .collect();
-    let distances: Vec<u64> = lines[1]
-        .split(":")
-        .last()
-        .expect("distance : missing")
-        .split_whitespace()
-        .map(|s: &str| s.parse().expect("msg"))
-        .collect();
-
-    // dbg!(&times);
-    // dbg!(&distances);
-
-    let mut win_options_per_race: Vec<u32> = Vec::new();
-
-    for (time, distance) in zip(times, distances) {
-        let win_options = compute_win_options(time, distance);
-
-        win_options_per_race.push(win_options);
-    }
+    let times: Vec<u64> = parse::labeled(lines[0], "Time:")
+        .and_then(parse::ints)
+        .expect("should have a line of times");
+    let distances: Vec<u64> = parse::labeled(lines[1], "Distance:")
+        .and_then(parse::ints)
+        .expect("should have a line of distances");
 
-    // dbg!(&win_options_per_race);
+    let win_options_per_race =
+        zip(times, distances).map(|(time, distance)| compute_win_options(time, distance));
 
-    Some(win_options_per_race.into_iter().product())
+    Some(win_options_per_race.product())
 }
 
-fn compute_win_options(time: u64, distance: u64) -> u32 {
-    let min_distance_to_win = distance + 1;
-
-    let discriminant = ((time.pow(2) - 4 * (min_distance_to_win)) as f64).sqrt();
-
-    let min_hold_time: f64 = (time as f64 - discriminant) / 2.;
-    let max_hold_time: f64 = (time as f64 + discriminant) / 2.;
+/// The hold times that beat `distance` in a race of `time` milliseconds. Empty if the race cannot be won.
+///
+/// Holding for `h` travels `h * (time - h)`, so the winning hold times are those with
+/// `h² - time * h + distance + 1 <= 0`, i.e. `(2h - time)² <= time² - 4 * (distance + 1)`.
+/// The discriminant is computed exactly in `u128` and compared through its integer square root.
+pub fn winning_hold_times(time: u64, distance: u64) -> RangeInclusive<u64> {
+    let (time, distance) = (u128::from(time), u128::from(distance));
+
+    let Some(discriminant) = (time * time).checked_sub(4 * (distance + 1)) else {
+        return RangeInclusive::new(1, 0);
+    };
+    let root = isqrt(discriminant).expect("unsigned numbers have a square root");
+
+    // |2h - time| <= root, and root <= time.
+    let (first, last) = ((time - root).div_ceil(2), (time + root) / 2);
+    if first > last {
+        return RangeInclusive::new(1, 0);
+    }
 
-    let min_hold_time = min_hold_time.ceil() as u32;
-    let max_hold_time = max_hold_time.floor() as u32;
+    let hold = |h: u128| u64::try_from(h).expect("hold times lie within the race time");
+    hold(first)..=hold(last)
+}
 
-    let amount_of_options = max_hold_time - min_hold_time + 1;
+fn compute_win_options(time: u64, distance: u64) -> u64 {
+    let hold_times = winning_hold_times(time, distance);
 
-    amount_of_options
+    if hold_times.is_empty() {
+        0
+    } else {
+        hold_times.end() - hold_times.start() + 1
+    }
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let lines: Vec<&str> = input.lines().collect();
-
-    let mut times = lines[0]
-        .split(":")
-        .last()
-        .expect("times : missing")
+/// The number on a line with the spaces between its digits removed.
+fn kerned_number(line: &str, label: &str) -> u64 {
+    let mut digits = parse::labeled(line, label)
+        .unwrap_or_else(|e| panic!("invalid race: {e}"))
         .to_string();
-    times.retain(|c| !c.is_whitespace());
+    digits.retain(|c| !c.is_whitespace());
 
-    let time: u64 = times.parse().expect("could not parse time");
-
-    let mut distances = lines[1]
-        .split(":")
-        .last()
-        .expect("distances : missing")
-        .to_string();
-    distances.retain(|c| !c.is_whitespace());
+    digits
+        .parse()
+        .unwrap_or_else(|e| panic!("could not parse {label} {digits:?}: {e}"))
+}
 
-    let distance: u64 = distances.parse().expect("could not parse distance");
+pub fn part_two(input: &str) -> Option<u64> {
+    let lines: Vec<&str> = input.lines().collect();
 
-    // dbg!(time, distance);
+    let time = kerned_number(lines[0], "Time:");
+    let distance = kerned_number(lines[1], "Distance:");
 
     Some(compute_win_options(time, distance))
 }
@@ -93,4 +91,56 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(71503));
     }
+
+    #[test]
+    fn test_hold_times() {
+        assert_eq!(winning_hold_times(7, 9), 2..=5);
+        assert_eq!(winning_hold_times(30, 200), 11..=19);
+        assert!(
+            winning_hold_times(7, 12).is_empty(),
+            "12 is the best possible"
+        );
+        assert_eq!(compute_win_options(7, 12), 0);
+        assert_eq!(compute_win_options(0, 0), 0);
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        for time in 0..80 {
+            for distance in 0..1700 {
+                let winners: Vec<u64> = (0..=time)
+                    .filter(|&hold| hold * (time - hold) > distance)
+                    .collect();
+
+                let hold_times = winning_hold_times(time, distance);
+                assert_eq!(
+                    hold_times.clone().collect::<Vec<_>>(),
+                    winners,
+                    "time {time}, distance {distance}"
+                );
+                assert_eq!(compute_win_options(time, distance), winners.len() as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_huge_numbers() {
+        assert_eq!(winning_hold_times(u64::MAX, 0), 1..=u64::MAX - 1);
+
+        // Past 2⁵³, where a float square root loses precision.
+        let time = (1 << 60) + 12_345;
+        for distance in [0, 1 << 40, u64::MAX] {
+            let hold_times = winning_hold_times(time, distance);
+            let travelled = |hold: u64| u128::from(hold) * u128::from(time - hold);
+
+            assert!(travelled(*hold_times.start()) > distance.into());
+            assert!(travelled(*hold_times.end()) > distance.into());
+            assert!(travelled(hold_times.start() - 1) <= distance.into());
+            assert!(travelled(hold_times.end() + 1) <= distance.into());
+        }
+
+        // Holding for 2³² travels 2⁶⁴, one more than the longest distance.
+        assert_eq!(winning_hold_times(1 << 33, u64::MAX), 1 << 32..=1 << 32);
+        assert_eq!(compute_win_options(1 << 33, u64::MAX - 1), 3);
+    }
 }
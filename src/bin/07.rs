@@ -1,16 +1,12 @@
-use std::cmp::Ordering;
-use std::collections::BTreeMap;
-
-use std::collections::BinaryHeap;
-
-use counter::Counter;
+use std::error::Error;
+use std::fmt::Display;
 
 use crate::HandType::*;
 
-advent_of_code::solution!(7);
+advent_of_code::solution!(7, commands: ["--explain" => explain]);
 
-#[derive(PartialEq, PartialOrd, Debug, Eq, Ord, Hash)]
-enum HandType {
+#[derive(PartialEq, PartialOrd, Debug, Eq, Ord, Hash, Clone, Copy)]
+pub enum HandType {
     HighCard,
     OnePair,
     TwoPair,
@@ -20,142 +16,221 @@ enum HandType {
     FiveOfAKind,
 }
 
-fn compute_hand_type(cards: &str) -> HandType {
-    let cards_counter = cards.chars().collect::<Counter<_>>().most_common_ordered();
-
-    let n_unique_cards = cards_counter.len();
-
-    assert!(n_unique_cards <= 5);
-    assert!(n_unique_cards >= 1);
-
-    match n_unique_cards {
-        5 => HighCard,
-        4 => OnePair,
-        3 => match cards_counter[0].1 {
-            3 => ThreeOfAKind,
-            2 => TwoPair,
-            _ => unreachable!(),
-        },
-        2 => match cards_counter[0].1 {
-            3 => FullHouse,
-            4 => FourOfAKind,
-            _ => unreachable!(),
-        },
-        1 => FiveOfAKind,
-        0 | 6.. => unreachable!(),
+impl HandType {
+    const ALL: [HandType; 7] = [
+        HighCard,
+        OnePair,
+        TwoPair,
+        ThreeOfAKind,
+        FullHouse,
+        FourOfAKind,
+        FiveOfAKind,
+    ];
+
+    /// The type of a hand, from the amounts of its most and second most common card.
+    fn from_counts(most: usize, second: usize) -> Self {
+        match (most, second) {
+            (5.., _) => FiveOfAKind,
+            (4, _) => FourOfAKind,
+            (3, 2..) => FullHouse,
+            (3, _) => ThreeOfAKind,
+            (2, 2) => TwoPair,
+            (2, _) => OnePair,
+            _ => HighCard,
+        }
     }
 }
 
-fn compute_hand_type_joker(cards: &str) -> HandType {
-    let mut cards_counter = cards.chars().collect::<Counter<_>>();
-
-    let n_jokers = cards_counter.remove(&'J');
-
-    match n_jokers {
-        Some(5) => cards_counter[&'J'] += 5,
-        Some(n) => {
-            let most_common_card = cards_counter.most_common_ordered().first().unwrap().0;
-            cards_counter[&most_common_card] += n;
-        }
-        None => (),
+impl Display for HandType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            HighCard => "high card",
+            OnePair => "one pair",
+            TwoPair => "two pair",
+            ThreeOfAKind => "three of a kind",
+            FullHouse => "full house",
+            FourOfAKind => "four of a kind",
+            FiveOfAKind => "five of a kind",
+        };
+
+        f.write_str(name)
     }
+}
 
-    let cards_counter = cards_counter.most_common_ordered();
-
-    let n_unique_cards = cards_counter.len();
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidHand {
+    UnknownCard(char),
+    Size { expected: usize, found: usize },
+}
 
-    assert!(n_unique_cards <= 5);
-    assert!(n_unique_cards >= 1);
+impl Error for InvalidHand {}
 
-    match n_unique_cards {
-        5 => HighCard,
-        4 => OnePair,
-        3 => match cards_counter[0].1 {
-            3 => ThreeOfAKind,
-            2 => TwoPair,
-            _ => unreachable!(),
-        },
-        2 => match cards_counter[0].1 {
-            3 => FullHouse,
-            4 => FourOfAKind,
-            _ => unreachable!(),
-        },
-        1 => FiveOfAKind,
-        0 | 6.. => unreachable!(),
+impl Display for InvalidHand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidHand::UnknownCard(card) => write!(f, "unknown card {card:?}"),
+            InvalidHand::Size { expected, found } => {
+                write!(f, "expected {expected} cards, found {found}")
+            }
+        }
     }
 }
 
-#[derive(Debug)]
-struct Hand<'a> {
-    cards: &'a str,
-    value: u64,
-    card_order: &'a str,
+/// How hands of Camel Cards are classified and ordered.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    /// The cards from strongest to weakest.
+    order: Vec<char>,
+    /// A card that counts as whichever card makes the best hand.
+    wildcard: Option<char>,
+    hand_size: usize,
+    /// The bits each card takes in a sort key.
+    card_bits: u32,
 }
 
 static CARD_ORDER: &str = "AKQJT98765432";
 static CARD_ORDER_JOKER: &str = "AKQT98765432J";
 
-impl Ord for Hand<'_> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        for (ca, cb) in self.cards.chars().zip(other.cards.chars()) {
-            let ca_strength = self.card_order.find(ca).expect("did not find card");
-            let cb_strength = self.card_order.find(cb).expect("did not find card");
-
-            if ca_strength < cb_strength {
-                return Ordering::Greater;
-            } else if cb_strength < ca_strength {
-                return Ordering::Less;
-            }
+impl Rules {
+    /// `order` lists the cards from strongest to weakest. A `wildcard` should be part of it.
+    ///
+    /// # Panics
+    /// If a sort key of the hand size does not fit an `u64`.
+    pub fn new(order: &str, wildcard: Option<char>, hand_size: usize) -> Self {
+        let order: Vec<char> = order.chars().collect();
+        let card_bits = usize::BITS - order.len().leading_zeros();
+
+        assert!(
+            hand_size * card_bits as usize + 3 <= 64,
+            "hands of {hand_size} cards do not fit a sort key"
+        );
+
+        Self {
+            order,
+            wildcard,
+            hand_size,
+            card_bits,
         }
+    }
 
-        Ordering::Equal
+    pub fn standard() -> Self {
+        Self::new(CARD_ORDER, None, 5)
     }
-}
 
-impl PartialOrd for Hand<'_> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    pub fn jokers() -> Self {
+        Self::new(CARD_ORDER_JOKER, Some('J'), 5)
     }
-}
 
-impl PartialEq for Hand<'_> {
-    fn eq(&self, other: &Self) -> bool {
-        self.cards == other.cards
+    /// The strength of `card`, the weakest card is `0`.
+    fn strength(&self, card: char) -> Result<u64, InvalidHand> {
+        let index = self
+            .order
+            .iter()
+            .position(|&c| c == card)
+            .ok_or(InvalidHand::UnknownCard(card))?;
+
+        Ok((self.order.len() - 1 - index) as u64)
     }
-}
 
-impl Eq for Hand<'_> {}
+    /// The type of `cards`, with wildcards joining the most common other card.
+    pub fn hand_type(&self, cards: &str) -> HandType {
+        let mut counts: Vec<(char, usize)> = vec![];
+        let mut wildcards = 0;
+
+        for card in cards.chars() {
+            if Some(card) == self.wildcard {
+                wildcards += 1;
+            } else if let Some((_, count)) = counts.iter_mut().find(|(c, _)| *c == card) {
+                *count += 1;
+            } else {
+                counts.push((card, 1));
+            }
+        }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let hands: Vec<(&str, u64)> = parse_hands(&input);
+        let mut counts: Vec<usize> = counts.into_iter().map(|(_, count)| count).collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
 
-    let mut map_by_type: BTreeMap<HandType, BinaryHeap<Hand>> = BTreeMap::new();
+        let most = counts.first().copied().unwrap_or(0) + wildcards;
+        let second = counts.get(1).copied().unwrap_or(0);
 
-    for (cards, value) in &hands {
-        let hand_type = compute_hand_type(&cards);
+        HandType::from_counts(most, second)
+    }
 
-        let entry = map_by_type.entry(hand_type).or_default();
+    /// A key that orders hands like the game does: by type, then card by card.
+    /// The type takes the highest bits, followed by the strength of every card.
+    pub fn sort_key(&self, cards: &str) -> Result<u64, InvalidHand> {
+        let found = cards.chars().count();
+        if found != self.hand_size {
+            return Err(InvalidHand::Size {
+                expected: self.hand_size,
+                found,
+            });
+        }
+
+        cards
+            .chars()
+            .try_fold(self.hand_type(cards) as u64, |key, card| {
+                Ok(key << self.card_bits | self.strength(card)?)
+            })
+    }
 
-        entry.push(Hand {
-            cards: cards,
-            value: *value,
-            card_order: CARD_ORDER,
-        });
+    /// The type of the hand a key returned by [`Self::sort_key`] was built from.
+    fn key_type(&self, key: u64) -> HandType {
+        HandType::ALL[(key >> (self.hand_size * self.card_bits as usize)) as usize]
     }
+}
 
-    let mut ranked_hands: Vec<Hand> = Vec::new();
+/// A hand with its place in the ranking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankedHand<'a> {
+    pub cards: &'a str,
+    pub bid: u64,
+    pub hand_type: HandType,
+    /// 1 for the weakest hand.
+    pub rank: u64,
+}
 
-    for hands in map_by_type.into_values() {
-        ranked_hands.append(&mut hands.into_sorted_vec());
+impl RankedHand<'_> {
+    pub fn winnings(&self) -> u64 {
+        self.rank * self.bid
     }
+}
 
-    Some(
-        ranked_hands
-            .iter()
-            .enumerate()
-            .map(|(i, hand)| (i + 1) as u32 * hand.value as u32)
-            .sum(),
-    )
+/// All hands from weakest to strongest.
+pub fn rank<'a>(hands: &[(&'a str, u64)], rules: &Rules) -> Vec<RankedHand<'a>> {
+    let mut keyed: Vec<(u64, &str, u64)> = hands
+        .iter()
+        .map(|&(cards, bid)| {
+            let key = rules
+                .sort_key(cards)
+                .unwrap_or_else(|e| panic!("invalid hand {cards:?}: {e}"));
+            (key, cards, bid)
+        })
+        .collect();
+
+    keyed.sort_unstable_by_key(|&(key, ..)| key);
+
+    keyed
+        .into_iter()
+        .zip(1..)
+        .map(|((key, cards, bid), rank)| RankedHand {
+            cards,
+            bid,
+            hand_type: rules.key_type(key),
+            rank,
+        })
+        .collect()
+}
+
+fn total_winnings(input: &str, rules: &Rules) -> u64 {
+    rank(&parse_hands(input), rules)
+        .iter()
+        .map(RankedHand::winnings)
+        .sum()
+}
+
+pub fn part_one(input: &str) -> Option<u64> {
+    Some(total_winnings(input, &Rules::standard()))
 }
 
 fn parse_hands(input: &str) -> Vec<(&str, u64)> {
@@ -170,36 +245,45 @@ fn parse_hands(input: &str) -> Vec<(&str, u64)> {
     hands
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let hands: Vec<(&str, u64)> = parse_hands(&input);
-
-    let mut map_by_type: BTreeMap<HandType, BinaryHeap<Hand>> = BTreeMap::new();
-
-    for (cards, value) in &hands {
-        let hand_type = compute_hand_type_joker(&cards);
-
-        let entry = map_by_type.entry(hand_type).or_default();
-
-        entry.push(Hand {
-            cards: cards,
-            value: *value,
-            card_order: CARD_ORDER_JOKER,
-        });
-    }
-
-    let mut ranked_hands: Vec<Hand> = Vec::new();
+pub fn part_two(input: &str) -> Option<u64> {
+    Some(total_winnings(input, &Rules::jokers()))
+}
 
-    for hands in map_by_type.into_values() {
-        ranked_hands.append(&mut hands.into_sorted_vec());
-    }
+/// Prints the type, rank and winnings of every hand under both rule sets.
+pub fn explain(input: &str, _args: &[String]) -> Result<(), Box<dyn Error>> {
+    let hands = parse_hands(input);
+
+    for (part, rules) in [(1, Rules::standard()), (2, Rules::jokers())] {
+        println!("Part {part}:");
+        println!(
+            "{:>6}  {:<6}{:>6}  {:<16}{:>10}",
+            "rank", "cards", "bid", "type", "winnings"
+        );
+
+        let ranked = rank(&hands, &rules);
+        for hand in &ranked {
+            println!(
+                "{:>6}  {:<6}{:>6}  {:<16}{:>10}",
+                hand.rank,
+                hand.cards,
+                hand.bid,
+                hand.hand_type.to_string(),
+                hand.winnings()
+            );
+        }
 
-    let mut result = 0;
+        for hand_type in HandType::ALL.into_iter().rev() {
+            let count = ranked.iter().filter(|h| h.hand_type == hand_type).count();
+            if count > 0 {
+                println!("  {hand_type}: {count}");
+            }
+        }
 
-    for (i, hand) in ranked_hands.into_iter().enumerate() {
-        result += ((i as u32 + 1) * hand.value as u32) as u32;
+        let total: u64 = ranked.iter().map(RankedHand::winnings).sum();
+        println!("  total winnings: {total}\n");
     }
 
-    Some(result)
+    Ok(())
 }
 
 #[cfg(test)]
@@ -217,4 +301,46 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(5905));
     }
+
+    #[test]
+    fn test_hand_types() {
+        let (standard, jokers) = (Rules::standard(), Rules::jokers());
+
+        assert_eq!(standard.hand_type("KTJJT"), TwoPair);
+        assert_eq!(jokers.hand_type("KTJJT"), FourOfAKind);
+        assert_eq!(jokers.hand_type("JJJJJ"), FiveOfAKind);
+        assert_eq!(jokers.hand_type("2345J"), OnePair);
+        assert_eq!(jokers.hand_type("2233J"), FullHouse);
+        assert_eq!(standard.hand_type("23456"), HighCard);
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let jokers = Rules::jokers();
+        let key = |cards| jokers.sort_key(cards).unwrap();
+
+        assert!(key("JKKK2") < key("QQQQ2"), "J is the weakest card");
+        assert!(key("22223") < key("JJJJJ"), "the type decides first");
+        assert_eq!(jokers.sort_key("AAAAX"), Err(InvalidHand::UnknownCard('X')));
+        assert!(matches!(
+            jokers.sort_key("AAA"),
+            Err(InvalidHand::Size { found: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let rules = Rules::new("ABCDEFGHIJKLMNOPQ", Some('Q'), 7);
+        assert_eq!(rules.hand_type("AAQQBCD"), FourOfAKind);
+        assert_eq!(rules.hand_type("BBBBBBB"), FiveOfAKind);
+        assert!(rules.sort_key("AAAAAAB").unwrap() > rules.sort_key("BBBBBBB").unwrap());
+
+        let hands = [("AB", 1), ("BB", 10), ("BA", 100)];
+        let ranked = rank(&hands, &Rules::new("AB", None, 2));
+        assert_eq!(
+            ranked.iter().map(|h| h.cards).collect::<Vec<_>>(),
+            vec!["BA", "AB", "BB"]
+        );
+        assert_eq!(ranked[2].winnings(), 30);
+    }
 }
@@ -1,145 +1,300 @@
-use std::{
-    collections::{HashMap, HashSet},
-    mem::swap,
-};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+
+use advent_of_code::cycle::{Goals, Trajectory};
+use advent_of_code::dot::Graph;
+use advent_of_code::math::{crt, gcd, lcm};
+use advent_of_code::scan;
+
+advent_of_code::solution!(8, commands: ["--export-dot" => export_dot]);
+
+/// Where a ghost is: the node it is on and the index of the instruction it follows next.
+/// The walk only depends on this state, so every ghost eventually loops.
+pub type State<'a> = (&'a str, usize);
+
+#[derive(Debug, Clone)]
+pub struct Network<'a> {
+    pub instructions: Vec<char>,
+    /// The left and right neighbour of every node.
+    pub nodes: HashMap<&'a str, (&'a str, &'a str)>,
+}
 
-advent_of_code::solution!(8);
+impl<'a> Network<'a> {
+    fn step(&self, &(node, instruction): &State<'a>) -> State<'a> {
+        let (left, right) = self
+            .nodes
+            .get(node)
+            .unwrap_or_else(|| panic!("unknown node {node:?}"));
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let (instructions, states) = parse_input(input)?;
+        let next = match self.instructions[instruction] {
+            'L' => left,
+            'R' => right,
+            c => panic!("invalid instruction {c:?}"),
+        };
 
-    let current_state = "AAA";
+        (next, (instruction + 1) % self.instructions.len())
+    }
 
-    // return None;
+    /// Every state of the walk from `start` up to the end of its first loop.
+    pub fn trajectory(&self, start: &'a str) -> Trajectory<State<'a>> {
+        Trajectory::record((start, 0), |state| self.step(state))
+    }
 
-    Some(run_chain(current_state, &states, &instructions, |s| {
-        s == "ZZZ"
-    }))
+    /// The steps at which the walk from `start` is on an end node.
+    pub fn goals(&self, start: &'a str, is_end: impl Fn(&str) -> bool) -> Goals {
+        self.trajectory(start).goals(|&(node, _)| is_end(node))
+    }
 }
 
-fn parse_input(input: &str) -> Option<(Vec<char>, HashMap<&str, (&str, &str)>)> {
+fn parse_input(input: &str) -> Option<Network<'_>> {
     let mut lines_it = input.lines();
     let instructions: Vec<char> = lines_it.next()?.chars().collect();
-    let mut states = HashMap::new();
+    if instructions.is_empty() {
+        return None;
+    }
+
+    let mut nodes = HashMap::new();
     for line in lines_it {
-        if !line.contains('=') {
+        if line.trim().is_empty() {
             continue;
         }
 
-        let name = line.split("=").next().unwrap().trim();
-
-        let tuple_string = line
-            .split('(')
-            .last()
-            .expect("expected (")
-            .trim_matches(')');
-
-        let tuple: Vec<_> = tuple_string.split(',').map(|s| s.trim()).collect();
+        let (name, left, right) = scan!(line, "{} = ({}, {})" => (&str, &str, &str))
+            .unwrap_or_else(|e| panic!("invalid node {line:?}: {e}"));
 
-        assert!(tuple.len() == 2);
-
-        states.insert(name, (tuple[0], tuple[1]));
+        nodes.insert(name, (left, right));
     }
-    Some((instructions, states))
-}
 
-fn step_state<'a>(
-    instruction: char,
-    current_state: &str,
-    states: &HashMap<&str, (&'a str, &'a str)>,
-) -> &'a str {
-    match instruction {
-        'L' => return states[current_state].0,
-        'R' => return states[current_state].1,
-        _ => unreachable!(),
-    }
+    Some(Network {
+        instructions,
+        nodes,
+    })
 }
 
-fn run_chain(
-    starting_state: &str,
-    states_map: &HashMap<&str, (&str, &str)>,
-    instructions: &Vec<char>,
-    finish_condition: fn(&str) -> bool,
-) -> u64 {
-    let mut current_state = starting_state;
-
-    let mut steps = 0;
-
-    let mut seen = HashSet::new();
+pub fn part_one(input: &str) -> Option<u64> {
+    let network = parse_input(input)?;
 
-    while !finish_condition(&current_state) {
-        let instruction = instructions[steps % instructions.len()];
+    let goals = network.goals("AAA", |node| node == "ZZZ");
 
-        seen.insert(current_state);
+    let first = goals.iter().next();
+    first.map(|steps| steps as u64)
+}
 
-        current_state = step_state(instruction, current_state, &states_map);
+/// Why the ghosts never stand on end nodes at the same time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoSolution {
+    NoGhosts,
+    /// The ghost starting on this node never reaches an end node.
+    NeverEnds(String),
+    /// Every ghost reaches end nodes, but never all in the same step.
+    NeverTogether,
+    /// The ghosts only meet on end nodes after more steps than fit an `u64`.
+    Overflow,
+}
 
-        // dbg!(current_state);
+impl Display for NoSolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoSolution::NoGhosts => write!(f, "there are no start nodes"),
+            NoSolution::NeverEnds(start) => {
+                write!(f, "the ghost starting at {start} never reaches an end node")
+            }
+            NoSolution::NeverTogether => {
+                write!(f, "the ghosts are never on end nodes at the same step")
+            }
+            NoSolution::Overflow => {
+                write!(f, "the ghosts only meet on end nodes after too many steps")
+            }
+        }
+    }
+}
 
-        steps += 1;
+/// The first step at which all ghosts are on a goal.
+///
+/// Goals in the prefix of a ghost are checked one by one. Goals in the loops are combined with the
+/// Chinese remainder theorem, one congruence per ghost for every combination of goals. If every ghost
+/// only reaches a goal at the multiples of its loop length, the answer is simply their [`lcm`].
+pub fn first_common_goal(ghosts: &[(&str, Goals)]) -> Result<u64, NoSolution> {
+    if ghosts.is_empty() {
+        return Err(NoSolution::NoGhosts);
     }
 
-    steps as u64
-}
+    if let Some((start, _)) = ghosts
+        .iter()
+        .find(|(_, goals)| goals.in_prefix.is_empty() && goals.in_cycle.is_empty())
+    {
+        return Err(NoSolution::NeverEnds(start.to_string()));
+    }
 
-fn finish_condition_ends_with_z(current_state: &str) -> bool {
-    current_state.ends_with('Z')
-}
+    let is_multiple_of_length =
+        |goals: &Goals| goals.in_prefix.is_empty() && goals.in_cycle == [goals.cycle.length];
 
-fn greatest_common_divisor(mut a: u64, mut b: u64) -> u64 {
-    assert!(a > 0 && b > 0);
+    if ghosts.iter().all(|(_, goals)| is_multiple_of_length(goals)) {
+        return ghosts
+            .iter()
+            .try_fold(1, |steps, (_, goals)| lcm(steps, goals.cycle.length as u64))
+            .ok_or(NoSolution::Overflow);
+    }
 
-    while b != 0 {
-        if a > b {
-            swap(&mut a, &mut b);
+    let in_prefix = ghosts
+        .iter()
+        .flat_map(|(_, goals)| &goals.in_prefix)
+        .filter(|&&step| ghosts.iter().all(|(_, goals)| goals.is_goal(step)))
+        .map(|&step| step as u64)
+        .min();
+
+    // Residue, modulus and the step from which the congruence holds.
+    let mut combinations: Vec<(u64, u64, u64)> = vec![(0, 1, 0)];
+    let mut overflow = false;
+
+    for (_, goals) in ghosts {
+        let length = goals.cycle.length as u64;
+        let mut next = vec![];
+
+        for &(residue, modulus, from) in &combinations {
+            for &step in &goals.in_cycle {
+                let step = step as u64;
+
+                // Tell contradicting congruences apart from overflows, `crt` fails for both.
+                let g = gcd(modulus, length).expect("the gcd of unsigned integers exists");
+                if residue.abs_diff(step) % g != 0 {
+                    continue;
+                }
+
+                match crt(&[(residue, modulus), (step, length)]) {
+                    Some((residue, modulus)) => next.push((residue, modulus, from.max(step))),
+                    None => overflow = true,
+                }
+            }
         }
 
-        b = b % a;
+        combinations = next;
     }
 
-    a
-}
-
-fn least_common_multiple(a: u64, b: u64) -> u64 {
-    assert!(a > 0 && b > 0);
+    let in_cycles = combinations
+        .into_iter()
+        .filter_map(|(residue, modulus, from)| {
+            let rounds = from.saturating_sub(residue).div_ceil(modulus);
+            let step = rounds
+                .checked_mul(modulus)
+                .and_then(|offset| residue.checked_add(offset));
+            overflow |= step.is_none();
+            step
+        })
+        .min();
 
-    a * (b / greatest_common_divisor(a, b))
+    match in_prefix.into_iter().chain(in_cycles).min() {
+        Some(step) => Ok(step),
+        None if overflow => Err(NoSolution::Overflow),
+        None => Err(NoSolution::NeverTogether),
+    }
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let (instructions, states_map) = parse_input(input)?;
+    let network = parse_input(input)?;
 
-    let start_states: Vec<&str> = states_map
+    let mut starts: Vec<&str> = network
+        .nodes
         .keys()
-        .into_iter()
-        .filter(|s| s.ends_with("A"))
-        .map(|s| *s)
+        .copied()
+        .filter(|s| s.ends_with('A'))
         .collect();
+    starts.sort_unstable();
 
-    let state_steps: Vec<_> = start_states
+    let ghosts: Vec<(&str, Goals)> = starts
         .into_iter()
-        .map(|s| run_chain(s, &states_map, &instructions, finish_condition_ends_with_z))
+        .map(|start| (start, network.goals(start, |node| node.ends_with('Z'))))
         .collect();
 
-    // dbg!(&state_steps);
+    first_common_goal(&ghosts)
+        .inspect_err(|e| eprintln!("no solution: {e}"))
+        .ok()
+}
 
-    let lcm = state_steps
-        .into_iter()
-        .reduce(|acc, e| {
-            if acc > 0 {
-                least_common_multiple(acc, e)
-            } else {
-                e
+/// The colours of the ghost loops, repeated if there are more ghosts.
+const LOOP_COLOURS: [&str; 6] = ["blue", "orange", "purple", "brown", "deeppink", "darkcyan"];
+
+/// The network as a graph: left edges solid, right edges dashed, start nodes green and end nodes red.
+/// The nodes and edges of every ghost's loop are drawn in the colour of the ghost.
+pub fn network_graph(network: &Network) -> Graph {
+    let mut starts: Vec<&str> = network.nodes.keys().copied().collect();
+    starts.sort_unstable();
+    starts.retain(|node| node.ends_with('A'));
+
+    // The colour of every (node, instruction) edge that is part of a loop.
+    let mut loop_edges: HashMap<(&str, char), &str> = HashMap::new();
+    let mut loop_nodes: HashMap<&str, &str> = HashMap::new();
+
+    for (&start, colour) in starts.iter().zip(LOOP_COLOURS.iter().cycle()) {
+        let trajectory = network.trajectory(start);
+
+        for &(node, instruction) in &trajectory.states[trajectory.cycle.prefix..] {
+            loop_edges.insert((node, network.instructions[instruction]), colour);
+            loop_nodes.insert(node, colour);
+        }
+    }
+
+    let mut graph = Graph::directed("network");
+    graph.node_defaults().set("shape", "circle");
+
+    let mut nodes: Vec<(&str, &(&str, &str))> = network
+        .nodes
+        .iter()
+        .map(|(&node, edges)| (node, edges))
+        .collect();
+    nodes.sort_unstable();
+
+    for (node, _) in &nodes {
+        let attributes = graph.node(node);
+
+        if node.ends_with('A') {
+            attributes
+                .set("style", "filled")
+                .set("fillcolor", "palegreen");
+        } else if node.ends_with('Z') {
+            attributes.set("style", "filled").set("fillcolor", "salmon");
+        }
+        if let Some(colour) = loop_nodes.get(node) {
+            attributes.set("color", colour).set("penwidth", 2);
+        }
+    }
+
+    for (node, &(left, right)) in nodes {
+        for (turn, next, style) in [('L', left, "solid"), ('R', right, "dashed")] {
+            let attributes = graph.edge(node, next);
+            attributes.set("label", turn).set("style", style);
+
+            if let Some(colour) = loop_edges.get(&(node, turn)) {
+                attributes.set("color", colour).set("penwidth", 2);
             }
-        })
-        .expect("state_steps should have items");
+        }
+    }
+
+    graph
+}
+
+/// Writes the network to the file given after `--export-dot`, see [`network_graph`].
+pub fn export_dot(input: &str, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let path = args.first().ok_or("expected a file to write to")?;
+    let network = parse_input(input).ok_or("the input has no instructions")?;
+
+    let graph = network_graph(&network);
+    std::fs::write(path, graph.to_string())?;
 
-    Some(lcm)
+    println!(
+        "Wrote {} nodes and {} edges to \"{path}\"",
+        graph.node_count(),
+        graph.edge_count()
+    );
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::cycle::Cycle;
 
     #[test]
     fn test_part_one() {
@@ -157,7 +312,131 @@ mod tests {
 
     #[test]
     fn test_gcd() {
-        let result = greatest_common_divisor(54, 24);
-        assert_eq!(result, 6);
+        let result = advent_of_code::math::gcd(54_u64, 24);
+        assert_eq!(result, Some(6));
+    }
+
+    /// A network that always goes left, with the given `node -> next` edges.
+    fn chain(edges: &[(&str, &str)]) -> String {
+        let nodes: Vec<String> = edges
+            .iter()
+            .map(|(node, next)| format!("{node} = ({next}, {next})"))
+            .collect();
+
+        format!("L\n\n{}\n", nodes.join("\n"))
+    }
+
+    #[test]
+    fn test_offset_cycles() {
+        // 11A ends at odd steps, 22A at steps 2, 5, 8, ... The first-hit lcm would be 2.
+        let input = chain(&[
+            ("11A", "11Z"),
+            ("11Z", "11B"),
+            ("11B", "11Z"),
+            ("22A", "22B"),
+            ("22B", "22Z"),
+            ("22Z", "22C"),
+            ("22C", "22D"),
+            ("22D", "22Z"),
+        ]);
+        assert_eq!(part_two(&input), Some(5));
+
+        let network = parse_input(&input).unwrap();
+        let goals = network.goals("22A", |node| node.ends_with('Z'));
+        assert_eq!((goals.cycle.prefix, goals.cycle.length), (2, 3));
+        assert_eq!(goals.in_cycle, vec![2]);
+    }
+
+    #[test]
+    fn test_goals_in_prefix() {
+        // 33A only ends at step 1, before it loops on 33B.
+        let input = chain(&[
+            ("11A", "11Z"),
+            ("11Z", "11B"),
+            ("11B", "11Z"),
+            ("33A", "33Z"),
+            ("33Z", "33B"),
+            ("33B", "33B"),
+        ]);
+        assert_eq!(part_two(&input), Some(1));
+    }
+
+    #[test]
+    fn test_no_solution() {
+        let input = chain(&[
+            ("11A", "11Z"),
+            ("11Z", "11B"),
+            ("11B", "11Z"),
+            ("22A", "22Z"),
+            ("22Z", "22B"),
+            ("22B", "22C"),
+            ("22C", "22B"),
+            ("44A", "44B"),
+            ("44B", "44A"),
+            ("55A", "55B"),
+            ("55B", "55Z"),
+            ("55Z", "55B"),
+        ]);
+        let network = parse_input(&input).unwrap();
+        let ghost = |start| (start, network.goals(start, |node| node.ends_with('Z')));
+
+        assert_eq!(
+            first_common_goal(&[ghost("11A"), ghost("44A")]),
+            Err(NoSolution::NeverEnds("44A".to_string()))
+        );
+        assert_eq!(first_common_goal(&[ghost("11A"), ghost("22A")]), Ok(1));
+        assert_eq!(first_common_goal(&[]), Err(NoSolution::NoGhosts));
+
+        // 11A ends at odd steps, 55A at even steps.
+        assert_eq!(
+            first_common_goal(&[ghost("11A"), ghost("55A")]),
+            Err(NoSolution::NeverTogether)
+        );
+        assert_eq!(part_two(&input), None);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let ghost = |length: usize, in_cycle: usize| {
+            let goals = Goals {
+                cycle: Cycle { prefix: 0, length },
+                in_prefix: vec![],
+                in_cycle: vec![in_cycle],
+            };
+            ("A", goals)
+        };
+        let (a, b) = (1 << 40, (1 << 40) - 1);
+
+        assert_eq!(
+            first_common_goal(&[ghost(a, a), ghost(b, b)]),
+            Err(NoSolution::Overflow)
+        );
+        assert_eq!(
+            first_common_goal(&[ghost(a, 1), ghost(b, 2)]),
+            Err(NoSolution::Overflow)
+        );
+        // Odd and even steps still never meet, whatever the lengths.
+        assert_eq!(
+            first_common_goal(&[ghost(2, 1), ghost(a, 2)]),
+            Err(NoSolution::NeverTogether)
+        );
+    }
+
+    #[test]
+    fn test_network_graph() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 2);
+        let network = parse_input(&input).unwrap();
+        let dot = network_graph(&network).to_string();
+
+        assert!(dot.starts_with("digraph \"network\" {"));
+        assert!(dot.contains("\"11A\" [style=\"filled\", fillcolor=\"palegreen\"];"));
+        assert!(dot.contains(
+            "\"22Z\" [style=\"filled\", fillcolor=\"salmon\", color=\"orange\", penwidth=\"2\"];"
+        ));
+        assert!(dot.contains(
+            "\"11B\" -> \"11Z\" [label=\"R\", style=\"dashed\", color=\"blue\", penwidth=\"2\"];"
+        ));
+        assert!(dot.contains("\"11B\" -> \"XXX\" [label=\"L\", style=\"solid\"];"));
+        assert_eq!(dot.matches(" -> ").count(), 2 * network.nodes.len());
     }
 }
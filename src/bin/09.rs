@@ -1,70 +1,99 @@
-#![feature(iter_map_windows)]
+use std::fmt::Display;
 
-advent_of_code::solution!(9);
-
-pub fn part_one(input: &str) -> Option<i64> {
-    let sequences: Vec<Vec<i64>> = input
-        .lines()
-        .map(|s| {
-            s.split_ascii_whitespace()
-                .map(|s| s.parse().expect("strings should be numbers"))
-                .collect()
-        })
-        .collect();
+use advent_of_code::math;
+use advent_of_code::parse;
 
-    let extrapolations: Vec<i64> = sequences.iter().map(|seq| extraplotate(seq)).collect();
-
-    // dbg!(&extrapolations);
+advent_of_code::solution!(9);
 
-    Some(extrapolations.iter().sum())
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtrapolationError {
+    Empty,
+    /// No row of differences is all zeros, so the sequence does not follow a polynomial.
+    NeverZero,
+    /// A difference or the extrapolated value does not fit.
+    Overflow,
 }
 
-fn compute_differences(seq: &Vec<i64>) -> Vec<i64> {
-    seq.iter().map_windows(|[x, y]| *y - *x).collect()
+impl Display for ExtrapolationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtrapolationError::Empty => write!(f, "the sequence is empty"),
+            ExtrapolationError::NeverZero => {
+                write!(f, "the differences never become all zeros")
+            }
+            ExtrapolationError::Overflow => write!(f, "the values overflow"),
+        }
+    }
 }
 
-fn extraplotate(seq: &Vec<i64>) -> i64 {
-    let mut differences: Vec<Vec<i64>> = Vec::new();
-
-    differences.push(compute_differences(seq));
+/// The degree of the polynomial behind `sequence`: the amount of difference rows before one is all zeros.
+/// Needs at least one row of zeros to confirm the degree, so a sequence of `n` values has a degree below `n - 1`.
+pub fn degree(sequence: &[i64]) -> Result<usize, ExtrapolationError> {
+    if sequence.is_empty() {
+        return Err(ExtrapolationError::Empty);
+    }
 
-    while !differences
-        .last()
-        .expect("should have a last")
-        .iter()
-        .all(|x| *x == 0)
-    {
-        differences.push(compute_differences(
-            differences.last().expect("should have a last"),
-        ));
+    let mut row: Vec<i128> = sequence.iter().map(|&value| value.into()).collect();
+
+    for differences in 0_usize.. {
+        if row.is_empty() {
+            break;
+        }
+        if row.iter().all(|&value| value == 0) {
+            return Ok(differences.saturating_sub(1));
+        }
+
+        row = row
+            .windows(2)
+            .map(|pair| pair[1].checked_sub(pair[0]))
+            .collect::<Option<_>>()
+            .ok_or(ExtrapolationError::Overflow)?;
     }
 
-    differences.last_mut().expect("should have a last").push(0);
+    Err(ExtrapolationError::NeverZero)
+}
 
-    for i in (0..differences.len() - 1).rev() {
-        let prev_diff = *differences[i + 1].last().unwrap();
-        let cur_diff = *differences[i].last().unwrap();
+/// The value `steps` after the last value of `sequence`, or `-steps` before the first one for negative `steps`.
+/// Evaluates the polynomial through the first values with binomial coefficients, so the steps in between are
+/// never computed.
+pub fn extrapolate(sequence: &[i64], steps: i64) -> Result<i64, ExtrapolationError> {
+    let degree = degree(sequence)?;
 
-        (&mut differences[i]).push(cur_diff + prev_diff);
+    let x = if steps >= 0 {
+        (sequence.len() as i64 - 1).checked_add(steps)
+    } else {
+        Some(steps)
     }
+    .ok_or(ExtrapolationError::Overflow)?;
 
-    let delta_next = differences[0].last().expect("should be a sequence");
+    math::extrapolate(&sequence[..=degree], x).ok_or(ExtrapolationError::Overflow)
+}
 
-    seq.last().expect("should be a sequence") + delta_next
+fn parse_sequences(input: &str) -> Vec<Vec<i64>> {
+    parse::lines(input, parse::ints).unwrap_or_else(|e| panic!("invalid sequence: {e}"))
 }
 
-pub fn part_two(input: &str) -> Option<i64> {
-    let sequences_iter = input.lines().map(|s| {
-        s.split_ascii_whitespace()
-            .map(|s| s.parse().expect("strings should be numbers"))
-    });
+/// The sum of the values `steps` away from every sequence.
+fn sum_of_extrapolations(input: &str, steps: i64) -> Option<i64> {
+    let mut sum = 0_i64;
+
+    for (i, sequence) in parse_sequences(input).iter().enumerate() {
+        let value = extrapolate(sequence, steps)
+            .inspect_err(|e| eprintln!("cannot extrapolate line {}: {e}", i + 1))
+            .ok()?;
 
-    // Reverse sequence
-    let sequences: Vec<Vec<i64>> = sequences_iter.map(|seq| seq.rev().collect()).collect();
+        sum = sum.checked_add(value)?;
+    }
+
+    Some(sum)
+}
 
-    let extrapolations: Vec<i64> = sequences.iter().map(|seq| extraplotate(seq)).collect();
+pub fn part_one(input: &str) -> Option<i64> {
+    sum_of_extrapolations(input, 1)
+}
 
-    Some(extrapolations.iter().sum())
+pub fn part_two(input: &str) -> Option<i64> {
+    sum_of_extrapolations(input, -1)
 }
 
 #[cfg(test)]
@@ -82,4 +111,45 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(2));
     }
+
+    #[test]
+    fn test_steps() {
+        let sequence = [10, 13, 16, 21, 30, 45];
+        assert_eq!(degree(&sequence), Ok(3));
+
+        assert_eq!(extrapolate(&sequence, 0), Ok(45));
+        assert_eq!(extrapolate(&sequence, 1), Ok(68));
+        assert_eq!(extrapolate(&sequence, -1), Ok(5));
+        assert_eq!(extrapolate(&sequence, -2), Ok(-4));
+        assert_eq!(extrapolate(&sequence, -3), Ok(-19));
+
+        // Matches stepping one value at a time.
+        let mut values: Vec<i64> = sequence.to_vec();
+        for _ in 0..20 {
+            values.push(extrapolate(&values, 1).unwrap());
+        }
+        assert_eq!(extrapolate(&sequence, 20), Ok(*values.last().unwrap()));
+
+        assert_eq!(extrapolate(&[7, 7, 7], 1_000_000), Ok(7));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(extrapolate(&[], 1), Err(ExtrapolationError::Empty));
+        assert_eq!(
+            extrapolate(&[1, 2, 4, 8], 1),
+            Err(ExtrapolationError::NeverZero)
+        );
+        assert_eq!(extrapolate(&[5], 1), Err(ExtrapolationError::NeverZero));
+        assert_eq!(
+            extrapolate(&[0, i64::MAX], 1),
+            Err(ExtrapolationError::NeverZero)
+        );
+        assert_eq!(
+            extrapolate(&[0, i64::MAX / 2, i64::MAX - 1], 1),
+            Err(ExtrapolationError::Overflow)
+        );
+
+        assert_eq!(part_one("0 1 3 7 15\n1 2 3\n"), None);
+    }
 }
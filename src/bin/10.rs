@@ -1,51 +1,16 @@
 use std::{
     char,
-    cmp::Reverse,
-    collections::{BinaryHeap, HashMap, HashSet},
-    fmt::{self, Debug, Display},
-    str::FromStr,
+    fmt::{self, Display},
 };
 
+use advent_of_code::geometry::Direction;
+use advent_of_code::grid::{Grid, Point};
+use advent_of_code::search::bfs_reach;
+use advent_of_code::template::span;
+use advent_of_code::viz::{Glyph, Rgb, Visualizer};
 use Tile::*;
 
-advent_of_code::solution!(10);
-
-struct Grid<T: PartialEq + Copy> {
-    arr: Vec<T>,
-    pub width: usize,
-}
-
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-impl Display for Direction {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Direction::Up => write!(f, "Up"),
-            Direction::Right => write!(f, "Right"),
-            Direction::Down => write!(f, "Down"),
-            Direction::Left => write!(f, "Left"),
-        }
-    }
-}
-
-type Point = (usize, usize);
-
-impl Direction {
-    fn opposite(&self) -> Self {
-        match &self {
-            Self::Up => Self::Down,
-            Self::Right => Self::Left,
-            Self::Down => Self::Up,
-            Self::Left => Self::Right,
-        }
-    }
-}
+advent_of_code::solution!(10, visualize: visualize);
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -68,9 +33,9 @@ impl Display for Tile {
     }
 }
 
-impl Into<char> for Tile {
-    fn into(self) -> char {
-        self as u8 as char
+impl From<Tile> for char {
+    fn from(tile: Tile) -> char {
+        tile as u8 as char
     }
 }
 
@@ -93,6 +58,24 @@ impl TryFrom<char> for Tile {
 }
 
 impl Tile {
+    const PIPES: [Tile; 6] = [
+        NorthSouth, EastWest, NorthEast, NortWest, SouthWest, SouthEast,
+    ];
+
+    /// The box-drawing character of the pipe.
+    fn pipe(&self) -> char {
+        match self {
+            NorthSouth => '│',
+            EastWest => '─',
+            NorthEast => '└',
+            NortWest => '┘',
+            SouthWest => '┐',
+            SouthEast => '┌',
+            Ground => ' ',
+            Start => 'S',
+        }
+    }
+
     fn is_connected_to(&self, direction: &Direction) -> bool {
         match (&self, direction) {
             (NorthSouth, Direction::Up | Direction::Down) => true,
@@ -108,193 +91,45 @@ impl Tile {
     }
 }
 
-impl<T: PartialEq + Copy> Grid<T> {
-    // type Err = String;
-
-    fn get(&self, p: Point) -> T {
-        let (x, y) = p;
-
-        assert!(x < self.width);
-        assert!(y < self.height());
-
-        let index = self.width * y + x;
-
-        assert!(index < self.arr.len());
-
-        self.arr[index]
-    }
-
-    fn get_mut(&mut self, p: Point) -> &mut T {
-        let (x, y) = p;
-
-        let index = self.width * y + x;
-
-        assert!(index < self.arr.len());
-
-        self.arr.get_mut(index).unwrap()
-    }
-
-    fn replace_sub_grid(&mut self, at: Point, data: Vec<T>, dst_width: usize) {
-        assert!(data.len() % dst_width == 0);
-
-        let (x, y) = at;
-
-        let n_lines = data.len() / dst_width;
-
-        assert!(x + dst_width <= self.width);
-        assert!(y + n_lines <= self.height());
-
-        for line in 0..n_lines {
-            let dst_range =
-                (self.width * (y + line) + x)..(self.width * (y + line) + x + dst_width);
-
-            self.arr[dst_range].copy_from_slice(&data[line * dst_width..((line + 1) * dst_width)])
-        }
-    }
-
-    fn find_value(&self, value: T) -> Option<Point> {
-        let index: usize = self.arr.iter().position(|c| *c == value)?;
-
-        let y = index / self.width;
-        let x = index - (y * self.width);
-
-        Some((x, y).into())
-    }
-
-    fn index_to_xy(&self, index: usize) -> Point {
-        assert!(index < self.arr.len());
-
-        let y = index / self.width;
-        let x = index - (y * self.width);
-
-        (x, y)
-    }
-
-    fn neighbor_locations(&self, p: Point) -> Vec<(Point, Direction)> {
-        let (x, y) = p;
-
-        assert!(x < self.width);
-        assert!(y < self.height());
-
-        let mut neighbors: Vec<(Point, Direction)> = Vec::new();
-
-        if x > 0 {
-            neighbors.push(((x - 1, y), Direction::Left));
-        }
-
-        if x + 1 < self.width {
-            neighbors.push(((x + 1, y), Direction::Right));
-        }
-
-        if y > 0 {
-            neighbors.push(((x, y - 1), Direction::Up));
-        }
-
-        let height = self.height();
-
-        if y + 1 < height {
-            neighbors.push(((x, y + 1), Direction::Down));
-        }
-
-        neighbors
-    }
-
-    fn height(&self) -> usize {
-        self.arr.len() / self.width
-    }
-
-    fn touches_edge(&self, p: Point) -> bool {
-        self.neighbor_locations(p).len() < 4
-    }
+fn neighbor_locations<T>(grid: &Grid<T>, p: Point) -> Vec<(Point, Direction)> {
+    [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+    ]
+    .into_iter()
+    .filter_map(|direction| Some((grid.offset(p, direction.offset().into())?, direction)))
+    .collect()
 }
 
-impl Grid<Tile> {
-    fn connected_neighbors(&self, p: Point) -> Vec<Point> {
-        let current = self.get(p);
+fn connected_neighbors(grid: &Grid<Tile>, p: Point) -> Vec<Point> {
+    let current = grid[p];
 
-        let mut result = Vec::new();
+    let mut result = Vec::new();
 
-        for (p_other, direction) in self.neighbor_locations(p) {
-            let other = self.get(p_other);
+    for (p_other, direction) in neighbor_locations(grid, p) {
+        let other = grid[p_other];
 
-            let is_connected =
-                current.is_connected_to(&direction) && other.is_connected_to(&direction.opposite());
+        let is_connected =
+            current.is_connected_to(&direction) && other.is_connected_to(&direction.opposite());
 
-            if is_connected {
-                result.push((p_other));
-            }
+        if is_connected {
+            result.push(p_other);
         }
-
-        result
     }
-}
 
-impl<T: Copy + PartialEq> Grid<T> {
-    fn filled(width: usize, height: usize, value: T) -> Grid<T> {
-        let arr = vec![value; width * height];
-
-        Grid {
-            arr: arr,
-            width: width,
-        }
-    }
-}
-
-impl FromStr for Grid<Tile> {
-    type Err = String;
-
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let lines: Vec<&str> = input.lines().collect();
-
-        assert!(lines.len() >= 1);
-
-        let width = lines[0].len();
-
-        let arr: Vec<Tile> = lines
-            .iter()
-            .flat_map(|l| {
-                l.chars()
-                    .map(|c| c.try_into().expect("should parse all tiles"))
-            })
-            .collect();
-
-        Ok(Grid {
-            arr: arr,
-            width: width,
-        })
-    }
+    result
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     let grid: Grid<Tile> = input.parse().expect("should parse grid input");
 
-    let start_pos = grid
-        .find_value('S'.try_into().unwrap())
-        .expect("should find start S");
-
-    // map from seen node to distance
-    let mut seen: HashMap<(Point), u32> = HashMap::new();
-
-    seen.insert(start_pos, 0);
-
-    let mut queue = BinaryHeap::new();
-
-    queue.push((Reverse(0), start_pos));
+    let start_pos = grid.find(&Start).expect("should find start S");
 
-    while queue.len() > 0 {
-        let (distance, p) = queue.pop().expect("should have at least one item");
+    let distances = bfs_reach([start_pos], |&p| connected_neighbors(&grid, p));
 
-        for (nx, ny) in grid.connected_neighbors(p) {
-            if seen.contains_key(&(nx, ny)) {
-                assert!(*seen.get(&(nx, ny)).unwrap() <= distance.0 + 1);
-            } else {
-                seen.insert((nx, ny), distance.0 + 1);
-                queue.push((Reverse(distance.0 + 1), (nx, ny)));
-            }
-        }
-    }
-
-    Some(*seen.values().max().unwrap())
+    distances.into_values().max().map(|d| d as u32)
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -316,72 +151,59 @@ impl<T: Display> fmt::Display for State<T> {
     }
 }
 
-impl<T: Display + PartialEq + Clone + Copy> fmt::Debug for Grid<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        Ok(for (i, val) in self.arr.iter().enumerate() {
-            if i % self.width == 0 {
-                writeln!(f);
-            }
-
-            write!(f, "{}", val);
-        })
-    }
-}
-
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut grid: Grid<Tile> = input.parse().expect("should parse grid input");
-
-    let loop_dists = compute_loop(&mut grid);
-
-    let loop_locations: HashSet<&(Point)> = loop_dists.keys().collect();
-
-    let mut classifications: Grid<State<Tile>> =
-        Grid::filled(grid.width, grid.height(), State::Unkown);
-
-    for p in &loop_locations {
-        *classifications.get_mut(**p) = State::Loop(grid.get(**p));
-    }
-
-    dbg!(&classifications);
-
-    let mut classifications = enlargen_classifications(classifications);
+    let mut grid: Grid<Tile> = {
+        let _t = span("parse");
+        input.parse().expect("should parse grid input")
+    };
 
-    dbg!(&classifications);
+    let loop_dists = {
+        let _t = span("compute_loop");
+        compute_loop(&mut grid)
+    };
 
-    while let Some(seed_point) = classifications.find_value(State::Unkown) {
-        let mut seen = HashSet::new();
-        seen.insert(seed_point);
+    let classifications = classify(&grid, &loop_dists);
 
-        let mut queue = Vec::new();
-        queue.push(seed_point);
+    let _t = span("count_inside");
 
-        let mut enclosed = true;
+    Some(
+        classifications
+            .iter()
+            .filter_map(|((x, y), el)| {
+                if x > 0 && (x - 1) % 3 == 0 && y > 0 && (y - 1) % 3 == 0 {
+                    Some(el)
+                } else {
+                    None
+                }
+            })
+            .filter(|s| **s == State::Inside)
+            .count() as u32,
+    )
+}
 
-        while let Some(p) = queue.pop() {
-            match classifications.get(p) {
-                Loop(_) => (),
-                _ => {
-                    let neighbors = classifications.neighbor_locations(p);
+/// Classifies the locations of a grid three times the size of `grid`, so that the flood fill can
+/// squeeze between pipes. The center of each 3x3 block is the classification of the tile.
+fn classify(grid: &Grid<Tile>, loop_dists: &Grid<Option<u32>>) -> Grid<State<Tile>> {
+    let classifications: Grid<State<Tile>> =
+        Grid::from_fn(grid.width(), grid.height(), |p| match loop_dists[p] {
+            Some(_) => State::Loop(grid[p]),
+            None => State::Unkown,
+        });
+
+    let mut classifications = {
+        let _t = span("enlargen_classifications");
+        enlargen_classifications(classifications)
+    };
 
-                    for (n_point, _) in neighbors {
-                        if !seen.contains(&n_point)
-                            && !matches!(classifications.get(n_point), Loop(_))
-                        {
-                            seen.insert(n_point);
+    let _t = span("flood_fill");
 
-                            queue.push(n_point);
+    while let Some(seed_point) = classifications.find(&State::Unkown) {
+        let seen = classifications.flood_fill(seed_point, |_, state| !matches!(state, Loop(_)));
 
-                            if classifications.touches_edge(p) {
-                                enclosed = false;
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        let enclosed = !seen.iter().any(|p| classifications.is_edge(p));
 
-        for point in seen {
-            *classifications.get_mut(point) = if enclosed {
+        for point in seen.iter() {
+            classifications[point] = if enclosed {
                 State::Inside
             } else {
                 State::Outside
@@ -389,47 +211,64 @@ pub fn part_two(input: &str) -> Option<u32> {
         }
     }
 
-    dbg!(&classifications);
+    classifications
+}
 
-    Some(
-        classifications
-            .arr
-            .iter()
-            .enumerate()
-            .filter_map(|(i, el)| {
-                let (x, y) = classifications.index_to_xy(i);
+/// Draws the loop growing from the start in both directions, then the tiles it encloses.
+pub fn visualize(input: &str, viz: &mut Visualizer) {
+    let mut grid: Grid<Tile> = input.parse().expect("should parse grid input");
+    let start_pos = grid.find(&Start).expect("should find start S");
 
-                if x > 0 && (x - 1) % 3 == 0 && y > 0 && (y - 1) % 3 == 0 {
-                    Some(el)
-                } else {
-                    None
-                }
-            })
-            .filter(|s| **s == State::Inside)
-            .count() as u32,
-    )
+    let loop_dists = compute_loop(&mut grid);
+    let furthest = loop_dists
+        .iter()
+        .filter_map(|(_, d)| *d)
+        .max()
+        .unwrap_or_default();
+
+    let pipe = |p: Point| Glyph::new(grid[p].pipe());
+    let frame = |distance: u32| {
+        Grid::from_fn(grid.width(), grid.height(), |p| match loop_dists[p] {
+            _ if p == start_pos => pipe(p).fg(Rgb::BLACK).bg(Rgb::RED),
+            Some(d) if d <= distance => pipe(p).fg(Rgb::YELLOW),
+            _ => pipe(p).fg(Rgb::GRAY),
+        })
+    };
+
+    // Around 60 frames for the loop, however long it is.
+    let stride = (furthest / 60).max(1);
+    for distance in (0..furthest).step_by(stride as usize).chain([furthest]) {
+        viz.frame(frame(distance));
+    }
+
+    let classifications = classify(&grid, &loop_dists);
+    let drawn = frame(furthest);
+    viz.frame(Grid::from_fn(
+        grid.width(),
+        grid.height(),
+        |p| match classifications[(p.0 * 3 + 1, p.1 * 3 + 1)] {
+            State::Inside => drawn[p].bg(Rgb::GREEN),
+            _ => drawn[p],
+        },
+    ));
 }
 
 type T = Tile;
 fn enlargen_classifications(classifications: Grid<State<Tile>>) -> Grid<State<T>> {
-    let new_width = classifications.width * 3;
+    let new_width = classifications.width() * 3;
     let new_height = classifications.height() * 3;
 
     let mut new_grid: Grid<State<T>> = Grid::filled(new_width, new_height, State::Unkown);
 
-    for (index, tile) in classifications.arr.iter().enumerate() {
-        let (x, y) = classifications.index_to_xy(index);
-
+    for ((x, y), tile) in classifications.iter() {
         let new_x = x * 3;
         let new_y = y * 3;
 
-        let new_index = new_y * new_width + new_x;
-
         let new_values: Vec<State<T>> = map_tile_to_3x3(*tile);
 
-        assert!(new_values.len() == 9);
+        let new_values = Grid::from_vec(3, 3, new_values).expect("should map to 3x3 tiles");
 
-        new_grid.replace_sub_grid((new_x, new_y), new_values, 3);
+        new_grid.replace_sub_grid((new_x, new_y), &new_values);
     }
 
     new_grid
@@ -515,86 +354,50 @@ fn map_tile_to_3x3(tile: State<Tile>) -> Vec<State<T>> {
     }
 }
 
-fn compute_loop(grid: &mut Grid<Tile>) -> HashMap<(Point), u32> {
-    let start_pos = grid
-        .find_value('S'.try_into().unwrap())
-        .expect("should find start S");
-
-    // map from seen node to distance
-    let mut seen: HashMap<Point, (u32, Option<Direction>)> = HashMap::new();
+/// Follows the pipes leaving `start` towards `direction`.
+/// Returns the direction from `start` to the last pipe if they lead back to it.
+fn trace_to_start(grid: &Grid<Tile>, start: Point, direction: Direction) -> Option<Direction> {
+    let (mut p, mut heading) = (start, direction);
 
-    // let mut start_direction: HashMap<Point, Direction> = HashMap::new();
+    loop {
+        p = grid.offset(p, heading.offset().into())?;
+        let tile = grid[p];
 
-    seen.insert(start_pos, (0, None));
-
-    let mut queue = BinaryHeap::new();
+        if tile == Start {
+            return Some(heading.opposite());
+        }
+        if !tile.is_connected_to(&heading.opposite()) {
+            return None;
+        }
 
-    for (neighbor_pos, direction) in grid
-        .neighbor_locations(start_pos)
-        .into_iter()
-        .filter(|(p, _)| grid.connected_neighbors(start_pos).contains(p))
-    {
-        queue.push((Reverse(1), neighbor_pos, direction));
+        heading = Direction::CARDINAL
+            .into_iter()
+            .find(|d| *d != heading.opposite() && tile.is_connected_to(d))?;
     }
+}
 
-    let mut connected_directions = HashSet::new();
-
-    while queue.len() > 0 {
-        let (distance, point, start_direction) =
-            queue.pop().expect("should have at least one item");
-
-        for np in grid.connected_neighbors(point) {
-            if seen.contains_key(&np) {
-                let (neighbor_distance, neighbor_start_direction) = *seen.get(&np).unwrap();
-
-                assert!(neighbor_distance <= distance.0 + 1);
-
-                if let Some(neighbor_start_direction) = neighbor_start_direction {
-                    if start_direction != neighbor_start_direction {
-                        connected_directions.insert(start_direction);
-                        connected_directions.insert(neighbor_start_direction);
+/// Replaces the start with the pipe that joins the two ends of the loop through it, other pipes
+/// pointing at the start are ignored.
+/// Returns the distance of every tile of the loop from the start, [`None`] for tiles off the loop.
+fn compute_loop(grid: &mut Grid<Tile>) -> Grid<Option<u32>> {
+    let start_pos = grid.find(&Start).expect("should find start S");
 
-                        assert!(connected_directions.len() == 2);
-                    }
-                }
+    grid[start_pos] = Direction::CARDINAL
+        .into_iter()
+        .find_map(|direction| {
+            let back = trace_to_start(grid, start_pos, direction)?;
+            Tile::PIPES
+                .into_iter()
+                .find(|tile| tile.is_connected_to(&direction) && tile.is_connected_to(&back))
+        })
+        .expect("start should be part of a loop");
 
-                // assert!(Some(start_direction) == neighbor_start_direction);
-            } else {
-                seen.insert(np, (distance.0 + 1, Some(start_direction)));
-                queue.push((Reverse(distance.0 + 1), np, start_direction));
-            }
-        }
+    let mut distances = Grid::filled(grid.width(), grid.height(), None);
+    for (p, distance) in bfs_reach([start_pos], |&p| connected_neighbors(grid, p)) {
+        distances[p] = Some(distance as u32);
     }
 
-    // let connected_directions: Vec<Direction> = connected_directions.into_iter().collect();
-
-    // let connected_directions
-
-    let start_tile = match &connected_directions {
-        d if d.contains(&Direction::Up) && d.contains(&Direction::Down) => Tile::NorthSouth,
-        d if d.contains(&Direction::Left) && d.contains(&Direction::Right) => Tile::EastWest,
-        d if d.contains(&Direction::Up) && d.contains(&Direction::Right) => Tile::NorthEast,
-        d if d.contains(&Direction::Up) && d.contains(&Direction::Left) => Tile::NortWest,
-        d if d.contains(&Direction::Down) && d.contains(&Direction::Right) => Tile::SouthEast,
-        d if d.contains(&Direction::Down) && d.contains(&Direction::Left) => Tile::SouthWest,
-        ds => panic!(
-            "Unhandled case: {:?}",
-            ds.iter().collect::<Vec<&Direction>>()
-        ),
-    };
-
-    *grid.get_mut(start_pos) = start_tile;
-
-    HashMap::from_iter(seen.iter().filter_map(|(key, (dist, direction))| {
-        if match direction {
-            Some(direction) => connected_directions.contains(direction),
-            None => true,
-        } {
-            Some((*key, *dist))
-        } else {
-            None
-        }
-    }))
+    distances
 }
 
 #[cfg(test)]
@@ -613,7 +416,7 @@ mod tests {
 
         let grid = input.parse::<Grid<Tile>>().unwrap();
 
-        let start_pos = grid.find_value('S'.try_into().unwrap());
+        let start_pos = grid.find(&Start);
 
         assert_eq!(start_pos, Some((0, 2)));
     }
@@ -624,13 +427,27 @@ mod tests {
 
         let grid = input.parse::<Grid<Tile>>().unwrap();
 
-        let sp = grid.find_value('S'.try_into().unwrap()).unwrap();
+        let sp = grid.find(&Start).unwrap();
 
-        let result = grid.connected_neighbors((sp.0 + 1, sp.1));
+        let result = connected_neighbors(&grid, (sp.0 + 1, sp.1));
 
         assert_eq!(result, vec![(0, 2), (1, 1)]);
     }
 
+    #[test]
+    fn test_start_with_third_pipe() {
+        // The pipe above S points at it, but does not belong to the loop.
+        let input = ".|...\n.S-7.\n.|.|.\n.L-J.\n.....\n";
+        let mut grid: Grid<Tile> = input.parse().unwrap();
+
+        let distances = compute_loop(&mut grid);
+
+        assert!(grid[(1, 1)] == SouthEast);
+        assert_eq!(distances[(1, 0)], None);
+        assert_eq!(distances[(3, 3)], Some(4));
+        assert_eq!(part_two(input), Some(1));
+    }
+
     #[test]
     fn test_part_two_one() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
@@ -1,154 +1,102 @@
-use std::{f32::consts::LN_10, collections::HashSet, mem::swap};
+use advent_of_code::geometry::Pos;
+use advent_of_code::grid::Grid;
 
 advent_of_code::solution!(11);
 
-type Point = (usize, usize);
+const GALAXY: char = '#';
 
-
-fn get_col(grid: &Vec<Vec<char>>, col:usize) -> Vec<char> {
-    let mut col_vec = Vec::new();
-    
-    for row_vec in grid {
-        col_vec.push(row_vec[col]);
-    }
-
-    col_vec
-}
-
-fn iter_cols(grid: &Vec<Vec<char>>) -> Vec<Vec<char>> {
-    let mut col_vecs = Vec::new();
-    
-    for col in 0..grid[0].len() {
-        col_vecs.push(get_col(&grid, col));
-    }
-
-    col_vecs
-}
-
-fn find_in_grid(grid: &Vec<Vec<char>>, target: char) -> Vec<Point> {
-    let mut results = Vec::new();
-
-    for (row, row_vec) in grid.iter().enumerate() {
-        for (col, value) in row_vec.iter().enumerate() {
-            if *value == target {
-                results.push((row, col));
-            }
-        }
-    }
-
-    results
+fn parse_image(input: &str) -> Grid<char> {
+    input.parse().expect("should parse grid input")
 }
 
-fn debug_grid(grid: &Vec<Vec<char>>) {
-    dbg!(&grid.iter().map(|row| row.iter().map(|&c| c).collect::<String>()).collect::<Vec<_>>());
+fn is_empty<'a>(mut line: impl Iterator<Item = &'a char>) -> bool {
+    line.all(|&c| c != GALAXY)
 }
 
-fn pairwise_combinations(galaxies: &Vec<Point>) -> HashSet<(Point, Point)> {
-
-    let mut combinations = HashSet::new();
-
-    for &galaxy_1 in galaxies {
-        for &galaxy_2 in galaxies {
-            if galaxy_1 < galaxy_2 {
-                combinations.insert((galaxy_1, galaxy_2));
-            } else if galaxy_2 > galaxy_1 {
-                combinations.insert((galaxy_2, galaxy_1));
-            }
-        }
-    }
+/// Whether each row and each column of the image is free of galaxies.
+fn empty_lines(image: &Grid<char>) -> (Vec<bool>, Vec<bool>) {
+    let rows = image.rows().map(|row| is_empty(row.iter())).collect();
+    let columns = image.columns().map(is_empty).collect();
 
-    combinations
+    (rows, columns)
 }
 
-fn manhattan_distance(p1: &Point, p2: &Point) -> usize {
-    p1.0.abs_diff(p2.0) + p1.1.abs_diff(p2.1)
+/// The coordinate of every line once each empty line takes up `empty_space_amount` lines.
+fn expanded_coordinates(empty: &[bool], empty_space_amount: usize) -> Vec<usize> {
+    empty
+        .iter()
+        .scan(0, |next, &is_empty| {
+            let coordinate = *next;
+            *next += if is_empty { empty_space_amount } else { 1 };
+            Some(coordinate)
+        })
+        .collect()
 }
 
-fn process_grid_and_expand(input: &str) -> Vec<Vec<char>>{
-    let mut grid: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
-
-    // debug_grid(&grid);
-
-    let line_insertation_locations: Vec<usize> = (&grid).iter().enumerate().filter(|(_, line)| line.iter().all(|c| *c != '#')).map(|(i, _)| i).collect();
-
-    for (i, loc) in line_insertation_locations.iter().enumerate() {
-        let line = vec!['.'; grid[0].len()];
-        grid.insert(i + loc, line);
+/// The image with every empty row and column doubled.
+fn process_grid_and_expand(input: &str) -> Grid<char> {
+    let image = parse_image(input);
+    let (empty_rows, empty_columns) = empty_lines(&image);
+
+    // For every line of the expanded image, the line of the image it shows, if any.
+    let sources = |empty: &[bool]| -> Vec<Option<usize>> {
+        empty
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &is_empty)| [Some(i)].into_iter().chain(is_empty.then_some(None)))
+            .collect()
     };
+    let (ys, xs) = (sources(&empty_rows), sources(&empty_columns));
 
-    let column_insert_locations: Vec<usize> = iter_cols(&grid).iter().enumerate().filter(|(_, col)| col.iter().all(|c| *c != '#')).map(|(i, _)| i).collect();
-
-    for row in grid.iter_mut() {
-        for (i, loc) in column_insert_locations.iter().enumerate() {
-            row.insert(i + *loc, '.');
-        };
-    }
+    Grid::from_fn(xs.len(), ys.len(), |(x, y)| match (xs[x], ys[y]) {
+        (Some(x), Some(y)) => image[(x, y)],
+        _ => '.',
+    })
+}
 
-    return grid
+/// The sum of the distances between all pairs of galaxies.
+fn sum_of_distances(galaxies: &[Pos]) -> usize {
+    galaxies
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &a)| {
+            galaxies[i + 1..]
+                .iter()
+                .map(move |&b| a.manhattan_distance(b))
+        })
+        .sum()
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let mut grid = process_grid_and_expand(input);
-    
-    // debug_grid(&grid);
-
-    let galaxies = find_in_grid(&grid, '#');
-
-    let combinations = pairwise_combinations(&galaxies);
-
-    // dbg!(&combinations);
-
-    Some(combinations.iter().map(|(p1, p2)| manhattan_distance(p1, p2)).sum::<usize>() as usize)
+    let image = process_grid_and_expand(input);
+    let galaxies: Vec<Pos> = image
+        .positions(|&c| c == GALAXY)
+        .into_iter()
+        .map(Pos::from)
+        .collect();
+
+    Some(sum_of_distances(&galaxies))
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
     Some(compute_distance(input, 1000000))
 }
 
+/// The sum of the distances between all pairs of galaxies, with every empty row and column
+/// taking up `empty_space_amount` rows or columns.
 fn compute_distance(input: &str, empty_space_amount: usize) -> usize {
-    let grid: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
-
-    let galaxies = find_in_grid(&grid, '#');
-
-    let empty_rows: HashSet<usize> = (&grid).iter().enumerate().filter(|(_, line)| line.iter().all(|c| *c != '#')).map(|(i, _)| i).collect();
-    let empty_columns: HashSet<usize> = iter_cols(&grid).iter().enumerate().filter(|(_, col)| col.iter().all(|c| *c != '#')).map(|(i, _)| i).collect();
-
-    let galaxy_pairs = pairwise_combinations(&galaxies);
-
-    galaxy_pairs.iter().map(|(p1, p2)| manhattan_distance_with_empty_space(*p1, *p2, &empty_rows, &empty_columns, empty_space_amount)).sum::<usize>()
-}
-
-fn manhattan_distance_with_empty_space(p1: (usize, usize), p2: (usize, usize), empty_rows: &HashSet<usize>, empty_columns: &HashSet<usize>, empty_space_amount: usize) -> usize {
-    let (x1, x2) = if p1.0 < p2.0 {(p1.0, p2.0)} else {(p2.0, p1.0)};
-    let (y1, y2) = if p1.1 < p2.1 {(p1.1, p2.1)} else {(p2.1, p1.1)};
-
-    let mut x1 = x1;
-
-    let mut distance = 0;
-
-    while x1 < x2 {
-        x1 += 1;
-        distance += {
-            match empty_columns.contains(&(x1 + 1)) {
-                true => empty_space_amount,
-                false => 1
-            }
-        }
-    }
-
-    let mut y1 = y1;
-
-    while y1 < y2 {
-        y1 += 1;
-        distance += {
-            match empty_rows.contains(&(y1 + 1)) {
-                true => empty_space_amount,
-                false => 1
-            }
-        }
-    }
-
-    distance
+    let image = parse_image(input);
+    let (empty_rows, empty_columns) = empty_lines(&image);
+    let ys = expanded_coordinates(&empty_rows, empty_space_amount);
+    let xs = expanded_coordinates(&empty_columns, empty_space_amount);
+
+    let galaxies: Vec<Pos> = image
+        .positions(|&c| c == GALAXY)
+        .into_iter()
+        .map(|(x, y)| Pos::new(xs[x], ys[y]))
+        .collect();
+
+    sum_of_distances(&galaxies)
 }
 
 #[cfg(test)]
@@ -158,9 +106,7 @@ mod tests {
     #[test]
     fn test_expansion() {
         let input = &advent_of_code::template::read_file("examples", DAY);
-        let mut grid = process_grid_and_expand(input);
-
-        debug_grid(&grid);
+        let grid = process_grid_and_expand(input);
 
         let expected = "....#........
 .........#...
@@ -176,14 +122,9 @@ mod tests {
 #....#.......
 ";
 
-    let expected: Vec<Vec<char>> = expected.lines().map(|l| l.chars().collect()).collect();
-
-    debug_grid(&expected);
-
-    assert_eq!(grid, expected);
+        assert_eq!(grid, parse_image(expected));
     }
 
-    
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -201,6 +142,4 @@ mod tests {
         let result = compute_distance(&advent_of_code::template::read_file("examples", DAY), 100);
         assert_eq!(result, 8410);
     }
-
-    
 }
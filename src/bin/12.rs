@@ -1,6 +1,6 @@
-use std::{option, vec, collections::HashMap};
+use advent_of_code::memo::{Dense, Memo};
 
-advent_of_code::solution!(12);
+advent_of_code::solution!(12, part_one: [part_one_brute_force]);
 
 fn recursive_options(conditions: &str, cgods: &Vec<usize>) -> usize {
     let has_unkown = conditions.contains('?');
@@ -42,9 +42,7 @@ fn process_line(input: &str) -> usize {
 
     // recursive_options(conditions, &cgods)
 
-    let mut sol = MemoizedSolution::new();
-
-    sol.count(conditions, cgods, false)
+    count_arrangements(conditions, &cgods)
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
@@ -59,85 +57,57 @@ pub fn part_one(input: &str) -> Option<usize> {
     Some(result)
 }
 
-struct MemoizedSolution<K> {
-    cache: HashMap<K, usize>
-}
+/// Tries every assignment of the unknown springs, kept to compare against the memoized solution.
+pub fn part_one_brute_force(input: &str) -> Option<usize> {
+    let mut result = 0;
 
-impl<'a> MemoizedSolution<(&'a str, Vec<usize>, bool)> {
+    for line in input.lines() {
+        let (conditions, cgods) = line.split_once(' ').expect("expected space char");
+        let cgods: Vec<usize> = cgods.split(',').map(|s| s.parse().expect("should be number")).collect();
 
-    fn new() -> Self {
-        Self {cache: HashMap::new()}
+        result += recursive_options(conditions, &cgods);
     }
 
-    fn count(&mut self, springs: &'a str, groups: Vec<usize>, in_group: bool) -> usize {
+    Some(result)
+}
 
-        let key = (springs, groups.clone(), in_group);
+fn count_arrangements(springs: &str, groups: &[usize]) -> usize {
+    let springs = springs.as_bytes();
 
-        if let Some(&result) = self.cache.get(&key) {
-            return result;
+    // `i` can end up one past the end, after a group that ends at the last spring.
+    let mut memo = Memo::dense((springs.len() + 2) * (groups.len() + 1)).named("arrangements");
+
+    arrangements(&mut memo, springs, groups, 0, 0)
+}
+
+/// Counts the arrangements of `springs[i..]` that match `groups[j..]`.
+fn arrangements(memo: &mut Memo<usize, usize, Dense<usize>>, springs: &[u8], groups: &[usize], i: usize, j: usize) -> usize {
+    let key = i * (groups.len() + 1) + j;
+
+    memo.get_or_compute(key, |memo, _| {
+        if i >= springs.len() {
+            return usize::from(j == groups.len());
         }
 
-        if springs.is_empty() {
-            match groups.iter().filter(|&&g| g > 0).count() == 0 {
-                true => return 1,
-                false => return 0,
+        let mut count = 0;
+
+        if springs[i] != b'#' {
+            count += arrangements(memo, springs, groups, i + 1, j);
+        }
+
+        if springs[i] != b'.' && j < groups.len() {
+            let end = i + groups[j];
+
+            let fits = end <= springs.len() && !springs[i..end].contains(&b'.');
+            let is_separated = springs.get(end) != Some(&b'#');
+
+            if fits && is_separated {
+                count += arrangements(memo, springs, groups, end + 1, j + 1);
             }
         }
 
-        let mut groups = groups.clone();
-
-        let (current_spring, tail) = springs.split_at(1);
-
-        let result = match current_spring {
-            "." => {
-                if in_group && groups[0] != 0 {
-                    return 0;
-                } else if in_group {
-                    // assert!(groups[0] == 0);
-                    groups.remove(0);   
-                }
-
-                self.count(tail, groups, false)
-            },
-            "#" => {
-                match groups.len() == 0 || groups[0] == 0 {
-                    true => 0,
-                    false => {
-                        groups[0] -= 1;
-                        self.count(tail, groups, true)
-                    }
-                }
-            },
-            "?" => {
-                if groups.len() == 0 {
-                    return self.count(tail, groups, false)
-                }
-
-                match groups[0] {
-                    0 => {
-                        groups.remove(0);
-                        self.count(tail, groups, false)
-                    },
-                    n => {
-                        let mut groups_dec = groups.clone();
-                        groups_dec[0] -= 1;
-
-                        let count_in_group = self.count(tail, groups_dec, true);
-
-                        match in_group {
-                            true => count_in_group,
-                            false => count_in_group + self.count(tail, groups, false)
-                        }
-                    }
-                }
-            },
-            _ => panic!("Invalid spring state")
-        };
-
-        self.cache.insert(key, result);
-
-        result
-    }
+        count
+    })
 }
 
 
@@ -149,9 +119,7 @@ fn process_line2(line: &str) -> usize {
     
     let cgods: Vec<usize> = cgods.split(',').map(|s| s.parse().expect("should be number")).collect();
 
-    let mut sol = MemoizedSolution::new();
-
-    sol.count(&conditions, cgods, false)
+    count_arrangements(&conditions, &cgods)
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
@@ -176,6 +144,12 @@ mod tests {
         assert_eq!(result, Some(21));
     }
 
+    #[test]
+    fn test_part_one_brute_force() {
+        let result = part_one_brute_force(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(21));
+    }
+
     #[test]
     fn test_part_two() {
         let result: Option<usize> = part_two(&advent_of_code::template::read_file("examples", DAY));
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use advent_of_code::scan;
 
 advent_of_code::solution!(15);
 
@@ -63,29 +63,6 @@ enum Operator {
 }
 
 
-#[derive(Debug)]
-enum ParseStepError {
-    OperatorParseError(String)
-}
-
-impl FromStr for Operator {
-    type Err = ParseStepError;
-    
-    fn from_str(value: &str) -> Result<Self, ParseStepError> {
-        let (op, focal_length) = value.split_at(1);
-
-        match op {
-            "-" => Ok(Self::Dash),
-            "=" => {
-                let focal_length = focal_length.parse().unwrap_or_else(|_| panic!("should be number: {} ({})", focal_length, value));
-
-                Ok(Self::Equals(focal_length))
-            }
-            _ => Err(ParseStepError::OperatorParseError(String::from(op)))
-        }
-    }
-}
-
 struct Step<'a> {
     label: &'a str,
     op: Operator
@@ -94,12 +71,14 @@ struct Step<'a> {
 impl<'a> From<&'a str> for Step<'a> {
 
     fn from(step: &'a str) -> Self {
-        let op_at = step.find(|c| c == '=' || c == '-').expect("should have operator char");
+        if let Ok((label, focal_length)) = scan!(step, "{}={}" => (&str, u32)) {
+            return Step { label, op: Operator::Equals(focal_length) };
+        }
+
+        let (label,) = scan!(step, "{}-" => (&str,))
+            .unwrap_or_else(|e| panic!("should be a step like rn=1 or cm-: {step:?} ({e})"));
 
-        let (label, tail) = step.split_at(op_at);
-        let op = tail.parse().expect("should be operator");
-        
-        Step { label, op }
+        Step { label, op: Operator::Dash }
     }
 }
 
@@ -1,87 +1,77 @@
-use std::collections::{HashMap, HashSet};
-advent_of_code::solution!(16);
+use advent_of_code::bitset::{BitGrid, DirectionMask};
+use advent_of_code::geometry::{Bounds, Direction, Pos as Point};
+use advent_of_code::grid::Grid;
+use advent_of_code::viz::{Glyph, Rgb, Visualizer};
 
+advent_of_code::solution!(16, visualize: visualize);
 
-#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
-struct Point {x: usize, y: usize}
 
 struct LightGrid {
-    cells: HashMap<Point, Cell>,
-}
-struct Cell {
-    cell_type: char,
-    beams: HashSet<Direction>,
-    activated: bool,
-}
-
-#[derive(Hash, PartialEq, Eq, Debug, Clone, Copy)]
-enum Direction {
-    Up, Right, Down, Left
+    cells: Grid<char>,
+    /// The directions beams entered each cell in.
+    beams: Grid<DirectionMask>,
+    activated: BitGrid,
+    bounds: Bounds,
 }
 
 impl LightGrid {
-    fn new(capacity: usize) -> Self { Self { cells: HashMap::with_capacity(capacity) } }
+    fn new(cells: Grid<char>) -> Self {
+        let bounds = cells.bounds();
+        Self {
+            beams: Grid::filled(bounds.width, bounds.height, DirectionMask::EMPTY),
+            activated: BitGrid::new(bounds.width, bounds.height),
+            cells,
+            bounds,
+        }
+    }
 
     fn activate(&mut self, location: Point, beam_direction: Direction) {
-        let cell = self.cells.get_mut(&location).unwrap();
-
-        cell.activated = true;
+        self.activated.insert(location);
 
-        // println!("activated {:?} for {:?}", &location, beam_direction);
-
-        if cell.beams.contains(&beam_direction) {
+        if !self.beams[location].insert(beam_direction) {
             // Stop recursion if already handled this incoming direction
-
-            // println!("stopping recursion {:?} for {:?}", &location, beam_direction);
             return;
         }
 
-        cell.beams.insert(beam_direction.clone());
-
-        let new_directions = match cell.cell_type {
-            '.' => vec![beam_direction],
-            '/' => match beam_direction {
-                Direction::Up => vec![Direction::Right],
-                Direction::Right => vec![Direction::Up],
-                Direction::Down => vec![Direction::Left],
-                Direction::Left => vec![Direction::Down]
-            },
-            '\\' => match beam_direction {
-                Direction::Up => vec![Direction::Left],
-                Direction::Right => vec![Direction::Down],
-                Direction::Down => vec![Direction::Right],
-                Direction::Left => vec![Direction::Up]
-            },
-            '|' => match beam_direction {
-                Direction::Up | Direction::Down => vec![beam_direction],
-                Direction::Left | Direction::Right => vec![Direction::Up, Direction::Down]
-            },
-            '-' => match beam_direction {
-                Direction::Left | Direction::Right => vec![beam_direction],
-                Direction::Up | Direction::Down => vec![Direction::Left, Direction::Right],
-            },
-            _ => panic!("unexpected cell type")
-        };
+        let new_directions = deflect(self.cells[location], beam_direction);
 
         for new_direction in new_directions {
-            if let Some(new_location) = self.get_neighbor(&location, &new_direction) {
+            if let Some(new_location) = location.step(new_direction, self.bounds) {
                 self.activate(new_location, new_direction);
             }
         }
     }
+}
 
-    fn get_neighbor(&self, point: &Point, direction: &Direction) -> Option<Point> {
-        let proposal = match direction {
-            Direction::Down => Point {x: point.x, y: point.y + 1},
-            Direction::Right => Point {x: point.x + 1, y: point.y},
-            Direction::Up => if point.y > 0 {Point {x: point.x, y: point.y - 1}} else {return None},
-            Direction::Left => if point.x > 0 {Point {x: point.x - 1, y: point.y}} else {return None},
-        };
-
-        match self.cells.contains_key(&proposal) {
-            true => Some(proposal),
-            false => None,
-        }
+/// The directions a beam continues in after entering a cell of `cell_type`.
+fn deflect(cell_type: char, beam_direction: Direction) -> Vec<Direction> {
+    match cell_type {
+        '.' => vec![beam_direction],
+        '/' => match beam_direction {
+            Direction::Up => vec![Direction::Right],
+            Direction::Right => vec![Direction::Up],
+            Direction::Down => vec![Direction::Left],
+            Direction::Left => vec![Direction::Down],
+            _ => unreachable!("beams only travel in cardinal directions"),
+        },
+        '\\' => match beam_direction {
+            Direction::Up => vec![Direction::Left],
+            Direction::Right => vec![Direction::Down],
+            Direction::Down => vec![Direction::Right],
+            Direction::Left => vec![Direction::Up],
+            _ => unreachable!("beams only travel in cardinal directions"),
+        },
+        '|' => match beam_direction {
+            Direction::Up | Direction::Down => vec![beam_direction],
+            Direction::Left | Direction::Right => vec![Direction::Up, Direction::Down],
+            _ => unreachable!("beams only travel in cardinal directions"),
+        },
+        '-' => match beam_direction {
+            Direction::Left | Direction::Right => vec![beam_direction],
+            Direction::Up | Direction::Down => vec![Direction::Left, Direction::Right],
+            _ => unreachable!("beams only travel in cardinal directions"),
+        },
+        _ => panic!("unexpected cell type")
     }
 }
 
@@ -96,19 +86,11 @@ fn count_activated(input: &str, start_point: Point, beam_direction: Direction) -
 
     grid.activate(start_point, beam_direction);
 
-    grid.cells.values().filter(|c| c.activated).count() as u64
+    grid.activated.count() as u64
 }
 
 fn parse_grid(input: &str) -> LightGrid {
-    let mut grid: LightGrid = LightGrid::new(input.len());
-
-    for (row, line) in input.lines().enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            let location = Point {x: col, y: row};
-            grid.cells.insert(location.clone(), Cell { cell_type: c, activated: false, beams: HashSet::new() });
-        }
-    }
-    grid
+    LightGrid::new(input.parse().expect("should parse grid input"))
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
@@ -144,6 +126,56 @@ pub fn part_two(input: &str) -> Option<u64> {
     activated.into_iter().max()
 }
 
+/// Draws the beam entering at the top left, moving every beam front one cell per frame.
+pub fn visualize(input: &str, viz: &mut Visualizer) {
+    let grid = parse_grid(input);
+
+    let mut seen = Grid::filled(grid.bounds.width, grid.bounds.height, DirectionMask::EMPTY);
+    let mut energized = BitGrid::new(grid.bounds.width, grid.bounds.height);
+    let mut front = vec![(Point { x: 0, y: 0 }, Direction::Right)];
+
+    while !front.is_empty() {
+        let mut next = vec![];
+
+        for (location, direction) in front {
+            if !seen[location].insert(direction) {
+                continue;
+            }
+
+            energized.insert(location);
+
+            for new_direction in deflect(grid.cells[location], direction) {
+                if let Some(new_location) = location.step(new_direction, grid.bounds) {
+                    next.push((new_location, new_direction));
+                }
+            }
+        }
+
+        let mut heads = BitGrid::new(grid.bounds.width, grid.bounds.height);
+        for (location, _) in &next {
+            heads.insert(*location);
+        }
+
+        viz.frame(Grid::from_fn(grid.bounds.width, grid.bounds.height, |(x, y)| {
+            let location = Point { x, y };
+            let glyph = match grid.cells[location] {
+                '.' => Glyph::new(' '),
+                c => Glyph::new(c).fg(Rgb::BLACK),
+            };
+
+            if heads.contains(location) {
+                glyph.bg(Rgb::WHITE)
+            } else if energized.contains(location) {
+                glyph.bg(Rgb::YELLOW)
+            } else {
+                glyph.fg(Rgb::GRAY)
+            }
+        }));
+
+        front = next;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
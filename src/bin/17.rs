@@ -1,6 +1,9 @@
-use std::{collections::{HashMap, HashSet, BinaryHeap}, panic::Location};
+use advent_of_code::geometry::{Bounds, Direction, Pos as Point};
+use advent_of_code::grid::Grid;
+use advent_of_code::search::{dijkstra_dense, Found};
+use advent_of_code::viz::{Glyph, Rgb, Visualizer};
 
-advent_of_code::solution!(17);
+advent_of_code::solution!(17, visualize: visualize);
 
 #[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, PartialOrd, Ord)]
 struct Key {
@@ -9,242 +12,121 @@ struct Key {
     direction: Direction, 
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-struct State {
-    cost: u64,
-    key: Key
-}
-
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.cost.cmp(&self.cost).then_with(|| self.key.cmp(&other.key))
-    }
-}
-
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, PartialOrd, Ord)]
-struct Point {x: usize, y: usize}
-
 type Cell = u8;
 
 struct CityGrid {
-    cells: HashMap<Point, Cell>,
-    // finish_location: Point,
+    cells: Grid<Cell>,
+    bounds: Bounds,
 }
 
 
 impl CityGrid {
-    fn new() -> Self { Self { cells: HashMap::new()} }
-
-    fn find_path(&mut self, start: Point, finish: Point) -> u64 {
-
-        let mut discovered: BinaryHeap<State> = BinaryHeap::new();
-
-        let mut seen: HashSet<Key> = HashSet::new();
-        
-        discovered.push(State { cost: 0, key: Key { location: start, direction: Direction::Right, steps_in_direction: 0 } });
-
-        while let Some(state) = discovered.pop() {
-
-            if state.key.location == finish {
-                return state.cost
-            }
-
-            if seen.contains(&state.key) {
-                continue
-            }
-
-            seen.insert(state.key);
-
-            let mut candidate_directions = vec![state.key.direction.turn_left(), state.key.direction.turn_right()];
-
-            if state.key.steps_in_direction < 3 {
-                candidate_directions.push(state.key.direction);
-            }
-
-            for new_direction in candidate_directions {
-                if let Some(new_location) = self.get_neighbor(&state.key.location, &new_direction) {
-                    let new_cost = state.cost + self.cells[&new_location] as u64;
-
-                    let new_steps_in_direction = match state.key.direction == new_direction {
-                        true => state.key.steps_in_direction + 1,
-                        false => 1, 
-                    };
+    fn new(cells: Grid<Cell>) -> Self { Self { bounds: cells.bounds(), cells } }
+
+    /// Moves of a crucible that has to go at least `min_straight` and at most `max_straight` blocks before turning.
+    fn successors(&self, key: &Key, min_straight: u8, max_straight: u8) -> Vec<(Key, u64)> {
+        let current_direction = key.direction;
+
+        let candidate_directions = match key.steps_in_direction {
+            // Only 0 when at the start
+            0 => Direction::CARDINAL.to_vec(),
+            steps if steps < min_straight => vec![current_direction],
+            steps if steps < max_straight => vec![current_direction, current_direction.turn_left(), current_direction.turn_right()],
+            _ => vec![current_direction.turn_left(), current_direction.turn_right()],
+        };
 
-                    discovered.push(
-                    State { 
-                            cost: new_cost, 
-                            key: Key { location: new_location, direction: new_direction, steps_in_direction: new_steps_in_direction } 
-                        }
-                    )
-                }
-            }
-        }
+        candidate_directions
+            .into_iter()
+            .filter_map(|new_direction| {
+                let new_location = key.location.step(new_direction, self.bounds)?;
 
-        panic!("Didn't reach finish")
+                let new_steps_in_direction = match current_direction == new_direction {
+                    true => key.steps_in_direction + 1,
+                    false => 1,
+                };
 
+                let new_key = Key { location: new_location, direction: new_direction, steps_in_direction: new_steps_in_direction };
 
+                Some((new_key, self.cells[new_location] as u64))
+            })
+            .collect()
     }
 
-    fn find_path_2(&mut self, start: Point, finish: Point) -> u64 {
-
-        let mut discovered: BinaryHeap<State> = BinaryHeap::new();
-
-        let mut seen: HashSet<Key> = HashSet::new();
-
-
-        discovered.push(State { cost: 0, key: Key { location: start, direction: Direction::Left, steps_in_direction: 0 } });
-        // discovered.push(State { cost: 0, key: Key { location: start, direction: Direction::Down, steps_in_direction: 0 } });
-
-        let mut dists: HashMap<Point, u64> = HashMap::new();
-
-        while let Some(state) = discovered.pop() {
-
-            if state.key.steps_in_direction >= 4 {
-                dists.entry(state.key.location).and_modify(|e| {*e = *e.min(&mut state.cost.clone())}).or_insert(state.cost);
-            }
-
-            // if state.key.location == finish && state.key.steps_in_direction >= 4 {
-            //     return state.cost
-            // }
-
-            if seen.contains(&state.key) {
-                continue
-            }
-
-            seen.insert(state.key);
-
-            let mut candidate_directions = Vec::new();
-
-            let current_direction = state.key.direction;
+    fn find_path(&self, start: Point, finish: Point, min_straight: u8, max_straight: u8) -> Option<Found<Key, u64>> {
+        let start = Key { location: start, direction: Direction::Right, steps_in_direction: 0 };
 
-            match state.key.steps_in_direction {
-                0 => {
-                    // Only 0 when at the start
-                    candidate_directions.append(&mut vec![Direction::Left, Direction::Right, Direction::Up, Direction::Down]);
-                }
-                1..=3 => candidate_directions.push(current_direction),
-                4..=9 => candidate_directions.append(&mut vec![current_direction, current_direction.turn_left(), current_direction.turn_right()]),
-                10 => candidate_directions.append(&mut vec![current_direction.turn_left(), current_direction.turn_right()]),
-                11.. => panic!("should not be possible to continue straight for more than 10")
-            }
+        // Every location, cardinal direction and amount of steps has its own slot.
+        let size = self.bounds.width * self.bounds.height * 4 * (max_straight as usize + 1);
+        let index = |key: &Key| {
+            let location = key.location.y * self.bounds.width + key.location.x;
+            let direction = key.direction.cardinal_index().expect("the crucible only moves in cardinal directions");
 
-            for new_direction in &candidate_directions {
-                if let Some(new_location) = self.get_neighbor(&state.key.location, new_direction) {
-                    let new_cost = state.cost + self.cells[&new_location] as u64;
-
-                    let new_steps_in_direction = match state.key.direction == *new_direction {
-                        true => state.key.steps_in_direction + 1,
-                        false => 1,
-                    };
-
-                    let new_state = State { 
-                            cost: new_cost, 
-                            key: Key { location: new_location, direction: *new_direction, steps_in_direction: new_steps_in_direction } 
-                    };
-
-                    discovered.push(new_state);
-                    
-                }
-            }
-        }
-
-        for y in 0..=finish.y {
-            for x in 0..=finish.x {
-                if let Some(&hl) = dists.get(&Point { x, y }) {
-                    print!("{:03} ", hl);
-                } else {
-                    print!("    ");
-                }
-            }
-            println!();
-        }
-
-        // panic!("Didn't reach finish")
-
-        *dists.get(&finish).unwrap()
-
-
-    }
-
-    fn get_neighbor(&self, point: &Point, direction: &Direction) -> Option<Point> {
-        let proposal = match direction {
-            Direction::Down => Point {x: point.x, y: point.y + 1},
-            Direction::Right => Point {x: point.x + 1, y: point.y},
-            Direction::Up => if point.y > 0 {Point {x: point.x, y: point.y - 1}} else {return None},
-            Direction::Left => if point.x > 0 {Point {x: point.x - 1, y: point.y}} else {return None},
+            (location * 4 + direction) * (max_straight as usize + 1) + key.steps_in_direction as usize
         };
 
-        match self.cells.contains_key(&proposal) {
-            true => Some(proposal),
-            false => None,
-        }
-    }
-}
-
-#[derive(Hash, PartialEq, Eq, Debug, Clone, Copy, PartialOrd, Ord)]
-enum Direction {
-    Up, Right, Down, Left
-}
-
-impl Direction {
-    fn turn_left(&self) -> Self {
-        match self {
-            Direction::Up => Direction::Left,
-            Direction::Right => Direction::Up,
-            Direction::Down => Direction::Right,
-            Direction::Left => Direction::Down,
-        }
-    }
-
-    fn turn_right(&self) -> Self {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
+        dijkstra_dense(
+            size,
+            index,
+            [start],
+            |key| self.successors(key, min_straight, max_straight),
+            |key| key.location == finish && key.steps_in_direction >= min_straight,
+        )
     }
 }
 
 fn parse_grid(input: &str) -> (CityGrid, Point) {
-    let width = input.lines().nth(0).unwrap().chars().count();
-    let height = input.lines().count();
+    let cells = input
+        .parse::<Grid<char>>()
+        .expect("should parse grid input")
+        .map(|c| c.to_digit(10).expect("should be a digit") as Cell);
 
-    let finish = Point { x: width - 1, y: height - 1 };
-
-    let mut grid: CityGrid = CityGrid::new();
-
-    for (row, line) in input.lines().enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            let location = Point {x: col, y: row};
-            grid.cells.insert(location.clone(), c.to_string().as_str().parse().expect("should be <='9'"));
-        }
-    }
+    let grid = CityGrid::new(cells);
+    let finish = Point { x: grid.bounds.width - 1, y: grid.bounds.height - 1 };
 
     (grid, finish)
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let (grid, finish) = parse_grid(&input);
+    let (grid, finish) = parse_grid(input);
 
-    let mut grid = grid;
-
-    return Some(grid.find_path(Point { x: 0, y: 0 }, finish))
+    grid.find_path(Point { x: 0, y: 0 }, finish, 1, 3).map(|found| found.cost)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let (grid, finish) = parse_grid(&input);
+    let (grid, finish) = parse_grid(input);
+
+    grid.find_path(Point { x: 0, y: 0 }, finish, 4, 10).map(|found| found.cost)
+}
+
+/// Draws the heat loss of every block and moves the crucibles of both parts along their paths.
+pub fn visualize(input: &str, viz: &mut Visualizer) {
+    let (grid, finish) = parse_grid(input);
 
-    let mut grid = grid;
+    let heat_map = grid.cells.map(|&heat_loss| {
+        let digit = char::from_digit(heat_loss as u32, 10).unwrap_or('?');
 
-    return Some(grid.find_path_2(Point { x: 0, y: 0 }, finish))
+        Glyph::new(digit).fg(Rgb::BLACK).bg(Rgb::heat((heat_loss as f64 - 1.0) / 8.0))
+    });
 
+    for (min_straight, max_straight, color) in [(1, 3, Rgb::WHITE), (4, 10, Rgb::RED)] {
+        let start = Point { x: 0, y: 0 };
+        let Some(found) = grid.find_path(start, finish, min_straight, max_straight) else {
+            continue;
+        };
+
+        let mut frame = heat_map.clone();
+        for key in &found.path {
+            let arrow = match key.direction {
+                Direction::Up => '^',
+                Direction::Right => '>',
+                Direction::Down => 'v',
+                _ => '<',
+            };
+
+            frame[key.location] = Glyph::new(arrow).fg(Rgb::BLACK).bg(color);
+            viz.frame(frame.clone());
+        }
+    }
 }
 
 #[cfg(test)]
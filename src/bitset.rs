@@ -0,0 +1,355 @@
+/// Compact sets for marking visited states without hashing.
+use std::fmt::Debug;
+
+use crate::geometry::Direction;
+use crate::grid::Point;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed-size set of the integers in `0..capacity`, one bit each.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+    capacity: usize,
+}
+
+impl BitSet {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            words: vec![0; capacity.div_ceil(WORD_BITS)],
+            capacity,
+        }
+    }
+
+    /// The amount of integers the set can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds `i`, returns whether it was not in the set yet.
+    ///
+    /// # Panics
+    /// If `i` is not below the capacity.
+    pub fn insert(&mut self, i: usize) -> bool {
+        assert!(
+            i < self.capacity,
+            "{i} is out of bounds for {}",
+            self.capacity
+        );
+
+        let word = &mut self.words[i / WORD_BITS];
+        let mask = 1 << (i % WORD_BITS);
+        let is_new = *word & mask == 0;
+        *word |= mask;
+
+        is_new
+    }
+
+    /// Removes `i`, returns whether it was in the set.
+    pub fn remove(&mut self, i: usize) -> bool {
+        if i >= self.capacity {
+            return false;
+        }
+
+        let word = &mut self.words[i / WORD_BITS];
+        let mask = 1 << (i % WORD_BITS);
+        let was_set = *word & mask != 0;
+        *word &= !mask;
+
+        was_set
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        i < self.capacity && self.words[i / WORD_BITS] & (1 << (i % WORD_BITS)) != 0
+    }
+
+    /// The amount of integers in the set.
+    pub fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Removes all integers, keeping the capacity.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Adds all integers of `other`, which must have the same capacity.
+    pub fn union_with(&mut self, other: &BitSet) {
+        assert_eq!(self.capacity, other.capacity, "capacities differ");
+
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    /// The integers in the set, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                if rest == 0 {
+                    return None;
+                }
+                let bit = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                Some(w * WORD_BITS + bit)
+            })
+        })
+    }
+}
+
+impl Debug for BitSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A set of locations of a `width` by `height` grid, one bit each.
+///
+/// ```
+/// # use advent_of_code::bitset::BitGrid;
+/// let mut visited = BitGrid::new(3, 2);
+/// assert!(visited.insert((2, 1)));
+/// assert!(!visited.insert((2, 1)));
+/// assert!(!visited.contains((5, 0)));
+/// assert_eq!(visited.iter().collect::<Vec<_>>(), vec![(2, 1)]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BitGrid {
+    bits: BitSet,
+    width: usize,
+    height: usize,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            bits: BitSet::new(width * height),
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, (x, y): Point) -> Option<usize> {
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    /// Adds `p`, returns whether it was not in the set yet.
+    ///
+    /// # Panics
+    /// If `p` is outside of the grid.
+    pub fn insert(&mut self, p: impl Into<Point>) -> bool {
+        let p = p.into();
+        let index = self
+            .index(p)
+            .unwrap_or_else(|| panic!("{p:?} is outside of the grid"));
+
+        self.bits.insert(index)
+    }
+
+    /// Removes `p`, returns whether it was in the set.
+    pub fn remove(&mut self, p: impl Into<Point>) -> bool {
+        self.index(p.into())
+            .is_some_and(|index| self.bits.remove(index))
+    }
+
+    pub fn contains(&self, p: impl Into<Point>) -> bool {
+        self.index(p.into())
+            .is_some_and(|index| self.bits.contains(index))
+    }
+
+    pub fn count(&self) -> usize {
+        self.bits.count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// The locations in the set, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        self.bits
+            .iter()
+            .map(|index| (index % self.width, index / self.width))
+    }
+}
+
+impl Debug for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A set of [`Direction`]s in a single byte, e.g. the directions a cell was entered from.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DirectionMask(u8);
+
+impl DirectionMask {
+    pub const EMPTY: DirectionMask = DirectionMask(0);
+
+    fn bit(direction: Direction) -> u8 {
+        1 << direction as u8
+    }
+
+    /// Adds `direction`, returns whether it was not in the set yet.
+    pub fn insert(&mut self, direction: Direction) -> bool {
+        let is_new = !self.contains(direction);
+        self.0 |= Self::bit(direction);
+        is_new
+    }
+
+    /// Removes `direction`, returns whether it was in the set.
+    pub fn remove(&mut self, direction: Direction) -> bool {
+        let was_set = self.contains(direction);
+        self.0 &= !Self::bit(direction);
+        was_set
+    }
+
+    pub fn contains(self, direction: Direction) -> bool {
+        self.0 & Self::bit(direction) != 0
+    }
+
+    pub fn count(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.0 = 0;
+    }
+
+    /// The directions in the set, clockwise starting with [`Direction::Up`].
+    pub fn iter(self) -> impl Iterator<Item = Direction> {
+        Direction::ALL
+            .into_iter()
+            .filter(move |&direction| self.contains(direction))
+    }
+}
+
+impl From<Direction> for DirectionMask {
+    fn from(direction: Direction) -> Self {
+        DirectionMask(Self::bit(direction))
+    }
+}
+
+impl FromIterator<Direction> for DirectionMask {
+    fn from_iter<I: IntoIterator<Item = Direction>>(iter: I) -> Self {
+        let mut mask = DirectionMask::EMPTY;
+        for direction in iter {
+            mask.insert(direction);
+        }
+        mask
+    }
+}
+
+impl Debug for DirectionMask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{BitGrid, BitSet, DirectionMask};
+    use crate::geometry::{Direction, Pos};
+
+    #[test]
+    fn bit_set() {
+        let mut set = BitSet::new(130);
+        assert!(set.is_empty());
+
+        for i in [0, 63, 64, 129, 64] {
+            set.insert(i);
+        }
+
+        assert_eq!(set.count(), 4);
+        assert!(set.contains(63) && set.contains(64));
+        assert!(!set.contains(1) && !set.contains(500));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 63, 64, 129]);
+
+        assert!(set.remove(63));
+        assert!(!set.remove(63));
+        assert_eq!(format!("{set:?}"), "{0, 64, 129}");
+
+        let mut other = BitSet::new(130);
+        other.insert(5);
+        set.union_with(&other);
+        assert_eq!(set.count(), 4);
+
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(set.capacity(), 130);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn bit_set_bounds() {
+        BitSet::new(10).insert(10);
+    }
+
+    #[test]
+    fn bit_grid() {
+        let mut grid = BitGrid::new(10, 7);
+        grid.insert(Pos::new(9, 0));
+        grid.insert((0, 6));
+        grid.insert((3, 3));
+
+        assert!(grid.contains(Pos::new(3, 3)));
+        assert!(!grid.contains((3, 4)));
+        assert!(!grid.contains((10, 0)), "outside of the grid");
+        assert_eq!(grid.count(), 3);
+        assert_eq!(
+            grid.iter().collect::<Vec<_>>(),
+            vec![(9, 0), (3, 3), (0, 6)]
+        );
+
+        assert!(grid.remove((9, 0)));
+        assert!(!grid.remove((11, 11)));
+        assert_eq!(grid.count(), 2);
+    }
+
+    #[test]
+    fn direction_mask() {
+        let mut mask: DirectionMask = [Direction::Left, Direction::Up].into_iter().collect();
+
+        assert!(mask.contains(Direction::Up));
+        assert!(!mask.contains(Direction::Down));
+        assert!(mask.insert(Direction::UpLeft));
+        assert!(!mask.insert(Direction::Left));
+        assert_eq!(
+            mask.iter().collect::<Vec<_>>(),
+            vec![Direction::Up, Direction::Left, Direction::UpLeft]
+        );
+
+        assert!(mask.remove(Direction::Up));
+        assert_eq!(mask.count(), 2);
+        assert_eq!(DirectionMask::from(Direction::Down).count(), 1);
+        assert!(DirectionMask::EMPTY.is_empty());
+    }
+}
@@ -0,0 +1,286 @@
+/// Cycle detection for simulations that repeat a deterministic step function.
+///
+/// Step `0` is the start state, step `n` is the state after applying the step function `n` times.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The shape of a sequence that eventually repeats: `prefix` steps lead into a loop of `length` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// The first step that is part of the loop.
+    pub prefix: usize,
+    /// The amount of steps after which the loop repeats, always at least `1`.
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The smallest step with the same state as step `n`, in `0..prefix + length`.
+    ///
+    /// ```
+    /// # use advent_of_code::cycle::Cycle;
+    /// let cycle = Cycle { prefix: 3, length: 4 };
+    /// assert_eq!(cycle.equivalent_step(2), 2);
+    /// assert_eq!(cycle.equivalent_step(1_000_000_000), 4);
+    /// ```
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.length
+        }
+    }
+}
+
+/// Brent's algorithm: finds the cycle while keeping only two states in memory.
+pub fn brent<S: Clone + PartialEq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Find the length by moving the hare ahead in doubling strides.
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Walk two states `length` apart until they meet at the start of the loop.
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    Cycle { prefix, length }
+}
+
+/// Floyd's tortoise and hare algorithm: finds the cycle while keeping only two states in memory.
+/// Usually slower than [`brent`], kept as a reference.
+pub fn floyd<S: Clone + PartialEq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let next = step(&hare);
+        hare = step(&next);
+    }
+
+    let mut prefix = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { prefix, length }
+}
+
+/// The state at step `n`, without simulating more than the prefix and one loop.
+///
+/// ```
+/// # use advent_of_code::cycle::state_at;
+/// let state = state_at(1_u64, |&x| x * 3 % 7, 1_000_000_000_000);
+/// assert_eq!(state, 4);
+/// ```
+pub fn state_at<S: Clone + PartialEq>(start: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let cycle = brent(start.clone(), &mut step);
+
+    let mut state = start;
+    for _ in 0..cycle.equivalent_step(n) {
+        state = step(&state);
+    }
+
+    state
+}
+
+/// Every state of a sequence up to the end of its first loop, found with a hash map of seen states.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trajectory<S> {
+    pub cycle: Cycle,
+    /// The states of steps `0..prefix + length`.
+    pub states: Vec<S>,
+}
+
+impl<S: Clone + Eq + Hash> Trajectory<S> {
+    /// Records states until one repeats.
+    pub fn record(start: S, mut step: impl FnMut(&S) -> S) -> Self {
+        let mut seen: HashMap<S, usize> = HashMap::new();
+        let mut states = vec![];
+        let mut state = start;
+
+        let prefix = loop {
+            if let Some(&first) = seen.get(&state) {
+                break first;
+            }
+
+            seen.insert(state.clone(), states.len());
+            let next = step(&state);
+            states.push(state);
+            state = next;
+        };
+
+        Self {
+            cycle: Cycle {
+                prefix,
+                length: states.len() - prefix,
+            },
+            states,
+        }
+    }
+}
+
+impl<S> Trajectory<S> {
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.states[self.cycle.equivalent_step(n)]
+    }
+
+    /// The steps at which `is_goal` holds, split into the prefix and one loop.
+    pub fn goals(&self, mut is_goal: impl FnMut(&S) -> bool) -> Goals {
+        let mut goals = Goals {
+            cycle: self.cycle,
+            in_prefix: vec![],
+            in_cycle: vec![],
+        };
+
+        for (n, state) in self.states.iter().enumerate() {
+            if is_goal(state) {
+                if n < self.cycle.prefix {
+                    goals.in_prefix.push(n);
+                } else {
+                    goals.in_cycle.push(n);
+                }
+            }
+        }
+
+        goals
+    }
+}
+
+/// The steps at which a goal is reached, see [`Trajectory::goals`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Goals {
+    pub cycle: Cycle,
+    /// Steps before the loop, each of them is reached once.
+    pub in_prefix: Vec<usize>,
+    /// Steps of the first loop. Each one is reached again every `cycle.length` steps.
+    pub in_cycle: Vec<usize>,
+}
+
+impl Goals {
+    pub fn is_goal(&self, n: usize) -> bool {
+        let step = self.cycle.equivalent_step(n);
+        self.in_prefix.contains(&step) || self.in_cycle.contains(&step)
+    }
+
+    /// Every step at which the goal is reached, in order. Infinite if there are goals in the loop.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let rounds = if self.in_cycle.is_empty() {
+            0..0
+        } else {
+            0..usize::MAX
+        };
+
+        let repeated = rounds.flat_map(move |round| {
+            self.in_cycle
+                .iter()
+                .map(move |&step| step + round * self.cycle.length)
+        });
+
+        self.in_prefix.iter().copied().chain(repeated)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{brent, floyd, state_at, Cycle, Trajectory};
+
+    /// 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2 -> ...
+    fn step(&x: &u32) -> u32 {
+        if x == 5 {
+            2
+        } else {
+            x + 1
+        }
+    }
+
+    #[test]
+    fn detectors_agree() {
+        let expected = Cycle {
+            prefix: 2,
+            length: 4,
+        };
+
+        assert_eq!(brent(0, step), expected);
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(Trajectory::record(0, step).cycle, expected);
+
+        assert_eq!(
+            brent(3, step),
+            Cycle {
+                prefix: 0,
+                length: 4
+            }
+        );
+        assert_eq!(
+            floyd(7, |&x| x),
+            Cycle {
+                prefix: 0,
+                length: 1
+            }
+        );
+    }
+
+    #[test]
+    fn jumps() {
+        let trajectory = Trajectory::record(0, step);
+
+        for n in 0..50 {
+            let mut state = 0;
+            for _ in 0..n {
+                state = step(&state);
+            }
+
+            assert_eq!(*trajectory.state_at(n), state);
+            assert_eq!(state_at(0, step, n), state);
+        }
+    }
+
+    #[test]
+    fn goals() {
+        let trajectory = Trajectory::record(0, step);
+        let goals = trajectory.goals(|&x| x % 2 == 1);
+
+        assert_eq!(goals.in_prefix, vec![1]);
+        assert_eq!(goals.in_cycle, vec![3, 5]);
+        assert_eq!(
+            goals.iter().take(5).collect::<Vec<_>>(),
+            vec![1, 3, 5, 7, 9]
+        );
+        assert!(goals.is_goal(1_000_001));
+        assert!(!goals.is_goal(1_000_000));
+
+        let none = trajectory.goals(|&x| x == 0);
+        assert_eq!(none.iter().collect::<Vec<_>>(), vec![0]);
+    }
+}
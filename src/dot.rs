@@ -0,0 +1,230 @@
+/// Writes graphs in the DOT language of Graphviz, e.g. to look at the structure of a puzzle input.
+///
+/// Render the output with `dot -Tsvg graph.dot -o graph.svg`.
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Attributes of a node, an edge or the whole graph, e.g. `color` or `style`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Attributes(Vec<(String, String)>);
+
+impl Attributes {
+    /// Sets `key` to `value`, replacing an earlier value.
+    pub fn set(&mut self, key: &str, value: impl Display) -> &mut Self {
+        let value = value.to_string();
+
+        match self.0.iter_mut().find(|(k, _)| k == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.0.push((key.to_string(), value)),
+        }
+
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Display for Attributes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+
+        write!(f, " [")?;
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{key}={}", Quoted(value))?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// An identifier in quotes, so that any text can be used.
+struct Quoted<'a>(&'a str);
+
+impl Display for Quoted<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' | '\\' => write!(f, "\\{c}")?,
+                '\n' => write!(f, "\\n")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+/// A graph of named nodes. Nodes are written in the order they were first added, edges in the
+/// order they were added. The same pair of nodes may be connected by several edges.
+///
+/// ```
+/// # use advent_of_code::dot::Graph;
+/// let mut graph = Graph::directed("network");
+/// graph.node("AAA").set("color", "green");
+/// graph.edge("AAA", "BBB").set("label", "L");
+///
+/// assert_eq!(
+///     graph.to_string(),
+///     "digraph \"network\" {\n  \"AAA\" [color=\"green\"];\n  \"BBB\";\n  \"AAA\" -> \"BBB\" [label=\"L\"];\n}\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    name: String,
+    directed: bool,
+    attributes: Attributes,
+    /// Defaults for all nodes and all edges.
+    node_defaults: Attributes,
+    edge_defaults: Attributes,
+    nodes: Vec<(String, Attributes)>,
+    index: HashMap<String, usize>,
+    edges: Vec<(usize, usize, Attributes)>,
+}
+
+impl Graph {
+    pub fn directed(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            directed: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn undirected(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            directed: false,
+            ..Self::default()
+        }
+    }
+
+    /// Attributes of the whole graph, e.g. `rankdir`.
+    pub fn attributes(&mut self) -> &mut Attributes {
+        &mut self.attributes
+    }
+
+    pub fn node_defaults(&mut self) -> &mut Attributes {
+        &mut self.node_defaults
+    }
+
+    pub fn edge_defaults(&mut self) -> &mut Attributes {
+        &mut self.edge_defaults
+    }
+
+    fn index(&mut self, id: &str) -> usize {
+        if let Some(&index) = self.index.get(id) {
+            return index;
+        }
+
+        self.nodes.push((id.to_string(), Attributes::default()));
+        self.index.insert(id.to_string(), self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    /// The attributes of node `id`, which is added if it is new.
+    pub fn node(&mut self, id: &str) -> &mut Attributes {
+        let index = self.index(id);
+        &mut self.nodes[index].1
+    }
+
+    /// Adds an edge from `from` to `to`, adding the nodes if they are new.
+    pub fn edge(&mut self, from: &str, to: &str) -> &mut Attributes {
+        let (from, to) = (self.index(from), self.index(to));
+        self.edges.push((from, to, Attributes::default()));
+
+        &mut self.edges.last_mut().expect("an edge was just added").2
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+}
+
+impl Display for Graph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (keyword, arrow) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+
+        writeln!(f, "{keyword} {} {{", Quoted(&self.name))?;
+
+        for (key, value) in &self.attributes.0 {
+            writeln!(f, "  {key}={};", Quoted(value))?;
+        }
+        if !self.node_defaults.is_empty() {
+            writeln!(f, "  node{};", self.node_defaults)?;
+        }
+        if !self.edge_defaults.is_empty() {
+            writeln!(f, "  edge{};", self.edge_defaults)?;
+        }
+
+        for (id, attributes) in &self.nodes {
+            writeln!(f, "  {}{attributes};", Quoted(id))?;
+        }
+
+        for (from, to, attributes) in &self.edges {
+            writeln!(
+                f,
+                "  {} {arrow} {}{attributes};",
+                Quoted(&self.nodes[*from].0),
+                Quoted(&self.nodes[*to].0)
+            )?;
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Graph;
+
+    #[test]
+    fn writes_graph() {
+        let mut graph = Graph::undirected("g");
+        graph.attributes().set("rankdir", "LR");
+        graph.edge_defaults().set("color", "gray");
+        graph
+            .edge("a", "b")
+            .set("style", "dashed")
+            .set("style", "bold");
+        graph.edge("a", "b");
+        graph.node("b").set("label", "say \"hi\"");
+
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.node("b").get("label"), Some("say \"hi\""));
+
+        let expected = [
+            "graph \"g\" {",
+            "  rankdir=\"LR\";",
+            "  edge [color=\"gray\"];",
+            "  \"a\";",
+            "  \"b\" [label=\"say \\\"hi\\\"\"];",
+            "  \"a\" -- \"b\" [style=\"bold\"];",
+            "  \"a\" -- \"b\";",
+            "}",
+            "",
+        ];
+        assert_eq!(graph.to_string(), expected.join("\n"));
+    }
+}
@@ -0,0 +1,358 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// A signed two-dimensional offset. `y` grows downwards, like rows in puzzle input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Vec2 {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2 { x: 0, y: 0 };
+
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    /// The manhattan length of the offset.
+    pub fn manhattan(self) -> usize {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, rhs: Vec2) -> Vec2 {
+        Vec2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, rhs: Vec2) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, rhs: Vec2) -> Vec2 {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<isize> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, rhs: isize) -> Vec2 {
+        Vec2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl From<(isize, isize)> for Vec2 {
+    fn from((x, y): (isize, isize)) -> Self {
+        Vec2::new(x, y)
+    }
+}
+
+impl From<Vec2> for (isize, isize) {
+    fn from(v: Vec2) -> Self {
+        (v.x, v.y)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The size of a rectangular area starting at `(0, 0)`, e.g. a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Bounds {
+    pub const fn new(width: usize, height: usize) -> Self {
+        Self { width, height }
+    }
+
+    pub fn contains(self, pos: Pos) -> bool {
+        pos.x < self.width && pos.y < self.height
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A location on a grid as `x` (column) and `y` (row), with `(0, 0)` in the top left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Pos {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Pos {
+    pub const fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// Moves by `offset`. Returns [`None`] if the result is negative or outside of `bounds`.
+    pub fn offset(self, offset: Vec2, bounds: Bounds) -> Option<Pos> {
+        let pos = Pos {
+            x: self.x.checked_add_signed(offset.x)?,
+            y: self.y.checked_add_signed(offset.y)?,
+        };
+        bounds.contains(pos).then_some(pos)
+    }
+
+    /// Moves one step towards `direction`. Returns [`None`] if the step leaves `bounds`.
+    ///
+    /// ```
+    /// # use advent_of_code::geometry::{Bounds, Direction, Pos};
+    /// let bounds = Bounds::new(3, 3);
+    /// assert_eq!(Pos::new(0, 0).step(Direction::Right, bounds), Some(Pos::new(1, 0)));
+    /// assert_eq!(Pos::new(0, 0).step(Direction::Up, bounds), None);
+    /// ```
+    pub fn step(self, direction: Direction, bounds: Bounds) -> Option<Pos> {
+        self.offset(direction.offset(), bounds)
+    }
+
+    /// The offset that moves `self` to `other`.
+    pub fn delta(self, other: Pos) -> Vec2 {
+        Vec2::new(
+            other.x as isize - self.x as isize,
+            other.y as isize - self.y as isize,
+        )
+    }
+
+    pub fn manhattan_distance(self, other: Pos) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The in-bounds neighbors towards the four cardinal directions.
+    pub fn neighbors4(self, bounds: Bounds) -> impl Iterator<Item = (Pos, Direction)> {
+        Direction::CARDINAL
+            .into_iter()
+            .filter_map(move |d| Some((self.step(d, bounds)?, d)))
+    }
+
+    /// The in-bounds neighbors towards all eight directions.
+    pub fn neighbors8(self, bounds: Bounds) -> impl Iterator<Item = (Pos, Direction)> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |d| Some((self.step(d, bounds)?, d)))
+    }
+}
+
+impl From<(usize, usize)> for Pos {
+    fn from((x, y): (usize, usize)) -> Self {
+        Pos::new(x, y)
+    }
+}
+
+impl From<Pos> for (usize, usize) {
+    fn from(pos: Pos) -> Self {
+        (pos.x, pos.y)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A compass direction on a grid. `Up` points towards row `0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// The four cardinal directions, clockwise starting with [`Direction::Up`].
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// All eight directions, clockwise starting with [`Direction::Up`].
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    fn from_index(index: usize) -> Self {
+        Self::ALL[index % 8]
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The offset of a single step towards this direction.
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Direction::Up => Vec2::new(0, -1),
+            Direction::UpRight => Vec2::new(1, -1),
+            Direction::Right => Vec2::new(1, 0),
+            Direction::DownRight => Vec2::new(1, 1),
+            Direction::Down => Vec2::new(0, 1),
+            Direction::DownLeft => Vec2::new(-1, 1),
+            Direction::Left => Vec2::new(-1, 0),
+            Direction::UpLeft => Vec2::new(-1, -1),
+        }
+    }
+
+    pub fn is_cardinal(self) -> bool {
+        self.index().is_multiple_of(2)
+    }
+
+    /// The position in [`Direction::CARDINAL`], [`None`] for diagonal directions.
+    /// Useful to give each cardinal direction its own slot in an array.
+    pub fn cardinal_index(self) -> Option<usize> {
+        match self {
+            Direction::Up => Some(0),
+            Direction::Right => Some(1),
+            Direction::Down => Some(2),
+            Direction::Left => Some(3),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn opposite(self) -> Self {
+        Self::from_index(self.index() + 4)
+    }
+
+    /// Rotates by 90 degrees counter-clockwise.
+    #[must_use]
+    pub fn turn_left(self) -> Self {
+        Self::from_index(self.index() + 6)
+    }
+
+    /// Rotates by 90 degrees clockwise.
+    #[must_use]
+    pub fn turn_right(self) -> Self {
+        Self::from_index(self.index() + 2)
+    }
+
+    /// Rotates clockwise by `eighths` of a full turn, e.g. `1` for 45 degrees.
+    #[must_use]
+    pub fn rotate(self, eighths: isize) -> Self {
+        Self::from_index((self.index() as isize + eighths).rem_euclid(8) as usize)
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    /// Parses `U`/`D`/`L`/`R`, `N`/`E`/`S`/`W` and `^`/`v`/`<`/`>`.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'U' | 'N' | '^' => Ok(Direction::Up),
+            'R' | 'E' | '>' => Ok(Direction::Right),
+            'D' | 'S' | 'v' => Ok(Direction::Down),
+            'L' | 'W' | '<' => Ok(Direction::Left),
+            _ => Err(ParseDirectionError),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => c.try_into(),
+            _ => Err(ParseDirectionError),
+        }
+    }
+}
+
+/// An error which can be returned when parsing a [`Direction`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDirectionError;
+
+impl Error for ParseDirectionError {}
+
+impl Display for ParseDirectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting one of U, D, L, R, N, E, S, W, ^, v, < or >")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Bounds, Direction, Pos, Vec2};
+
+    #[test]
+    fn rotations() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::UpRight.turn_right(), Direction::DownRight);
+        assert_eq!(Direction::Down.opposite(), Direction::Up);
+        assert_eq!(Direction::DownLeft.opposite(), Direction::UpRight);
+        assert_eq!(Direction::Up.rotate(-1), Direction::UpLeft);
+        assert_eq!(Direction::Up.rotate(9), Direction::UpRight);
+
+        for d in Direction::ALL {
+            assert_eq!(d.offset() + d.opposite().offset(), Vec2::ZERO);
+        }
+
+        for (i, d) in Direction::CARDINAL.into_iter().enumerate() {
+            assert_eq!(d.cardinal_index(), Some(i));
+        }
+        assert_eq!(Direction::DownLeft.cardinal_index(), None);
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!("U".parse(), Ok(Direction::Up));
+        assert_eq!('v'.try_into(), Ok(Direction::Down));
+        assert_eq!('<'.try_into(), Ok(Direction::Left));
+        assert!("UR".parse::<Direction>().is_err());
+        assert!("x".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn stepping() {
+        let bounds = Bounds::new(2, 2);
+        let origin = Pos::new(0, 0);
+
+        assert_eq!(origin.step(Direction::Left, bounds), None);
+        assert_eq!(
+            origin.step(Direction::DownRight, bounds),
+            Some(Pos::new(1, 1))
+        );
+        assert_eq!(Pos::new(1, 1).step(Direction::Down, bounds), None);
+        assert_eq!(origin.neighbors4(bounds).count(), 2);
+        assert_eq!(origin.neighbors8(bounds).count(), 3);
+        assert_eq!(origin.delta(Pos::new(1, 1)), Vec2::new(1, 1));
+        assert_eq!(origin.manhattan_distance(Pos::new(1, 1)), 2);
+    }
+}
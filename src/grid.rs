@@ -0,0 +1,580 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use crate::bitset::BitGrid;
+use crate::geometry::{Bounds, Pos};
+
+/// A location in a [`Grid`] as `(x, y)`, with `(0, 0)` in the top left corner.
+pub type Point = (usize, usize);
+
+/// A dense, rectangular two-dimensional grid stored in row-major order.
+///
+/// # Parsing
+/// Grids of any cell type that implements `TryFrom<char>` can be parsed from puzzle input,
+/// including `char`, `u8` and custom tile enums.
+///
+/// ```
+/// # use advent_of_code::grid::Grid;
+/// let grid: Grid<char> = "#.\n.#\n".parse().unwrap();
+/// assert_eq!(grid[(1, 1)], '#');
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Creates a [`Grid`] from cells in row-major order.
+    /// Returns [`None`] if the amount of cells doesn't match the dimensions.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Option<Self> {
+        if cells.len() != width * height {
+            return None;
+        }
+
+        Some(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    /// Creates a [`Grid`] by calling `f` for every location.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut f)
+            .collect();
+
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Parses a grid from lines of characters, converting each one with `f`.
+    pub fn parse_with<E>(
+        input: &str,
+        mut f: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Self, ParseGridError> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for (y, line) in input.lines().enumerate() {
+            let mut line_width = 0;
+
+            for (x, c) in line.chars().enumerate() {
+                let cell = f(c).map_err(|_| ParseGridError::InvalidCell {
+                    line: y + 1,
+                    column: x + 1,
+                    value: c,
+                })?;
+
+                cells.push(cell);
+                line_width += 1;
+            }
+
+            match width {
+                None => width = Some(line_width),
+                Some(expected) if expected != line_width => {
+                    return Err(ParseGridError::RaggedLine {
+                        line: y + 1,
+                        expected,
+                        found: line_width,
+                    })
+                }
+                Some(_) => {}
+            }
+
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                cells,
+                width,
+                height,
+            }),
+            _ => Err(ParseGridError::Empty),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The dimensions of the grid, e.g. for [`Pos::step`].
+    pub fn bounds(&self) -> Bounds {
+        Bounds::new(self.width, self.height)
+    }
+
+    /// Returns `true` if `p` lies inside the grid.
+    pub fn contains(&self, p: Point) -> bool {
+        p.0 < self.width && p.1 < self.height
+    }
+
+    /// Returns `true` if `p` lies on the outermost ring of the grid.
+    pub fn is_edge(&self, p: Point) -> bool {
+        self.contains(p)
+            && (p.0 == 0 || p.1 == 0 || p.0 + 1 == self.width || p.1 + 1 == self.height)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        if self.contains(p) {
+            Some(&self.cells[self.index_of(p)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        if self.contains(p) {
+            let index = self.index_of(p);
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    /// Sets the cell at `p`, returns the previous value or [`None`] if `p` is out of bounds.
+    pub fn set(&mut self, p: Point, value: T) -> Option<T> {
+        self.get_mut(p).map(|cell| std::mem::replace(cell, value))
+    }
+
+    fn index_of(&self, p: Point) -> usize {
+        p.1 * self.width + p.0
+    }
+
+    fn point_of(&self, index: usize) -> Point {
+        (index % self.width, index / self.width)
+    }
+
+    /// The cells in row-major order.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Iterates over all cells in row-major order, together with their location.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (self.point_of(i), cell))
+    }
+
+    /// Iterates over the locations of the grid in row-major order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        if y < self.height {
+            Some(&self.cells[y * self.width..(y + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> Option<impl DoubleEndedIterator<Item = &T>> {
+        if x < self.width {
+            Some(self.cells[x..].iter().step_by(self.width))
+        } else {
+            None
+        }
+    }
+
+    pub fn columns(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = impl DoubleEndedIterator<Item = &T>> + ExactSizeIterator
+    {
+        (0..self.width).map(|x| self.cells[x..].iter().step_by(self.width))
+    }
+
+    /// Returns the location of the first cell (in row-major order) matching `predicate`.
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.cells
+            .iter()
+            .position(&mut predicate)
+            .map(|i| self.point_of(i))
+    }
+
+    /// Returns the locations of all cells matching `predicate`.
+    pub fn positions(&self, mut predicate: impl FnMut(&T) -> bool) -> Vec<Point> {
+        self.iter()
+            .filter(|(_, cell)| predicate(cell))
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// The locations above, right of, below and left of `p` (in that order) that lie inside the grid.
+    pub fn neighbors4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        const OFFSETS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+        OFFSETS.iter().filter_map(move |&d| self.offset(p, d))
+    }
+
+    /// The up to eight locations surrounding `p` (clockwise, starting above) that lie inside the grid.
+    pub fn neighbors8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        const OFFSETS: [(isize, isize); 8] = [
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ];
+        OFFSETS.iter().filter_map(move |&d| self.offset(p, d))
+    }
+
+    /// Moves `p` by `(dx, dy)`, returns [`None`] if the result lies outside the grid.
+    pub fn offset(&self, p: Point, (dx, dy): (isize, isize)) -> Option<Point> {
+        let x = p.0.checked_add_signed(dx)?;
+        let y = p.1.checked_add_signed(dy)?;
+        self.contains((x, y)).then_some((x, y))
+    }
+
+    /// Collects all locations reachable from `start` through 4-neighbors for which `passable` holds.
+    /// `start` itself is included if it is passable.
+    pub fn flood_fill(&self, start: Point, mut passable: impl FnMut(Point, &T) -> bool) -> BitGrid {
+        let mut filled = BitGrid::new(self.width, self.height);
+
+        if !self.get(start).is_some_and(|cell| passable(start, cell)) {
+            return filled;
+        }
+
+        let mut stack = vec![start];
+        filled.insert(start);
+
+        while let Some(p) = stack.pop() {
+            for n in self.neighbors4(p) {
+                if !filled.contains(n) && passable(n, &self[n]) {
+                    filled.insert(n);
+                    stack.push(n);
+                }
+            }
+        }
+
+        filled
+    }
+
+    /// Labels every cell with the index of its 4-connected component.
+    /// Two neighboring cells are in the same component if `connected` holds for them.
+    ///
+    /// Returns the labels and the number of components.
+    pub fn connected_components(
+        &self,
+        mut connected: impl FnMut(&T, &T) -> bool,
+    ) -> (Grid<usize>, usize) {
+        let mut labels: Grid<Option<usize>> = Grid::filled(self.width, self.height, None);
+        let mut count = 0;
+
+        for start in self.points() {
+            if labels[start].is_some() {
+                continue;
+            }
+
+            labels[start] = Some(count);
+            let mut stack = vec![start];
+
+            while let Some(p) = stack.pop() {
+                for n in self.neighbors4(p) {
+                    if labels[n].is_none() && connected(&self[p], &self[n]) {
+                        labels[n] = Some(count);
+                        stack.push(n);
+                    }
+                }
+            }
+
+            count += 1;
+        }
+
+        (labels.map(|label| label.unwrap_or_default()), count)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Overwrites the cells starting at `at` (top left) with the contents of `other`.
+    ///
+    /// # Panics
+    /// If `other` doesn't fit into the grid at `at`.
+    pub fn replace_sub_grid(&mut self, at: Point, other: &Grid<T>) {
+        assert!(at.0 + other.width <= self.width && at.1 + other.height <= self.height);
+
+        for (y, row) in other.rows().enumerate() {
+            let start = self.index_of((at.0, at.1 + y));
+            self.cells[start..start + other.width].clone_from_slice(row);
+        }
+    }
+
+    /// Swaps rows and columns.
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |(x, y)| self[(y, x)].clone())
+    }
+
+    /// Rotates the grid by 90 degrees clockwise.
+    #[must_use]
+    pub fn rotate_clockwise(&self) -> Self {
+        Self::from_fn(self.height, self.width, |(x, y)| {
+            self[(y, self.height - 1 - x)].clone()
+        })
+    }
+
+    /// Rotates the grid by 90 degrees counter-clockwise.
+    #[must_use]
+    pub fn rotate_counter_clockwise(&self) -> Self {
+        Self::from_fn(self.height, self.width, |(x, y)| {
+            self[(self.width - 1 - y, x)].clone()
+        })
+    }
+
+    /// Mirrors the grid along the vertical axis, i.e. left becomes right.
+    #[must_use]
+    pub fn flip_horizontal(&self) -> Self {
+        Self::from_fn(self.width, self.height, |(x, y)| {
+            self[(self.width - 1 - x, y)].clone()
+        })
+    }
+
+    /// Mirrors the grid along the horizontal axis, i.e. top becomes bottom.
+    #[must_use]
+    pub fn flip_vertical(&self) -> Self {
+        Self::from_fn(self.width, self.height, |(x, y)| {
+            self[(x, self.height - 1 - y)].clone()
+        })
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Returns the location of the first cell (in row-major order) equal to `value`.
+    pub fn find(&self, value: &T) -> Option<Point> {
+        self.position(|cell| cell == value)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &Self::Output {
+        assert!(
+            self.contains(p),
+            "{p:?} is out of bounds for a {}x{} grid",
+            self.width,
+            self.height
+        );
+        &self.cells[self.index_of(p)]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut Self::Output {
+        assert!(
+            self.contains(p),
+            "{p:?} is out of bounds for a {}x{} grid",
+            self.width,
+            self.height
+        );
+        let index = self.index_of(p);
+        &mut self.cells[index]
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        &self[(pos.x, pos.y)]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        &mut self[(pos.x, pos.y)]
+    }
+}
+
+impl<T: TryFrom<char>> FromStr for Grid<T> {
+    type Err = ParseGridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, T::try_from)
+    }
+}
+
+/// Displays one line per row.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An error which can be returned when parsing a [`Grid`].
+/// Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGridError {
+    Empty,
+    RaggedLine {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidCell {
+        line: usize,
+        column: usize,
+        value: char,
+    },
+}
+
+impl Error for ParseGridError {}
+
+impl Display for ParseGridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseGridError::Empty => write!(f, "grid is empty"),
+            ParseGridError::RaggedLine {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line} has {found} cells, expected {expected} like the first line"
+            ),
+            ParseGridError::InvalidCell {
+                line,
+                column,
+                value,
+            } => write!(f, "invalid cell {value:?} at line {line}, column {column}"),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Grid, ParseGridError};
+
+    fn sample() -> Grid<char> {
+        "abc\ndef\n".parse().unwrap()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let grid = sample();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+
+        let bytes: Grid<u8> = "ab\ncd".parse().unwrap();
+        assert_eq!(bytes[(1, 1)], b'd');
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("".parse::<Grid<char>>(), Err(ParseGridError::Empty));
+        assert_eq!(
+            "ab\nc\n".parse::<Grid<char>>(),
+            Err(ParseGridError::RaggedLine {
+                line: 2,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            Grid::parse_with("12\n3x", |c| c.to_digit(10).ok_or(())),
+            Err(ParseGridError::InvalidCell {
+                line: 2,
+                column: 2,
+                value: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = sample();
+        let rows: Vec<String> = grid.rows().map(|r| r.iter().collect()).collect();
+        assert_eq!(rows, vec!["abc", "def"]);
+
+        let columns: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+        assert_eq!(columns, vec!["ad", "be", "cf"]);
+        assert_eq!(grid.column(3).is_none(), true);
+    }
+
+    #[test]
+    fn transformations() {
+        let grid = sample();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_counter_clockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed\n");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc\n");
+        assert_eq!(grid.rotate_clockwise().rotate_counter_clockwise(), grid);
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = sample();
+        assert_eq!(
+            grid.neighbors4((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbors8((1, 0)).count(), 5);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn flood_fill_and_components() {
+        let grid: Grid<char> = "..#\n###\n#..\n".parse().unwrap();
+
+        let filled = grid.flood_fill((0, 0), |_, c| *c == '.');
+        assert_eq!(filled.count(), 2);
+        assert!(filled.contains((1, 0)));
+
+        let (labels, count) = grid.connected_components(|a, b| a == b);
+        assert_eq!(count, 3);
+        assert_eq!(labels[(0, 0)], labels[(1, 0)]);
+        assert_eq!(labels[(2, 0)], labels[(0, 2)]);
+        assert_ne!(labels[(0, 0)], labels[(1, 2)]);
+    }
+}
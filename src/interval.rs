@@ -0,0 +1,382 @@
+/// Sets of half-open ranges and maps that shift parts of a number line by offsets.
+use std::ops::{Add, Range, Sub};
+
+/// A set of values stored as sorted, disjoint, non-adjacent ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: vec![] }
+    }
+
+    /// The ranges of the set, sorted by start. None of them are empty, overlapping or touching.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The smallest value in the set.
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.start <= value)
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        self.ranges.push(range);
+        self.normalise();
+    }
+
+    /// Sorts the ranges, drops empty ones and merges the ones that overlap or touch.
+    fn normalise(&mut self) {
+        self.ranges.retain(|range| range.start < range.end);
+        self.ranges.sort_unstable_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(self.ranges.len());
+
+        for range in self.ranges.drain(..) {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+
+        self.ranges = merged;
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges.iter().chain(&other.ranges).cloned().collect()
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+
+            if start < end {
+                ranges.push(start..end);
+            }
+
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// All values of `self` that are not in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let mut j = 0;
+
+        for range in &self.ranges {
+            let mut start = range.start;
+
+            // Skip the ranges of `other` that end before this one starts.
+            while other.ranges.get(j).is_some_and(|cut| cut.end <= start) {
+                j += 1;
+            }
+
+            let mut k = j;
+            while let Some(cut) = other.ranges.get(k).filter(|cut| cut.start < range.end) {
+                if start < cut.start {
+                    ranges.push(start..cut.start);
+                }
+                start = start.max(cut.end);
+                k += 1;
+            }
+
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+
+        Self { ranges }
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + Add<Output = T> + Default> IntervalSet<T> {
+    /// The number of values in the set.
+    pub fn size(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::default(), |size, range| size + (range.end - range.start))
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self {
+            ranges: iter.into_iter().collect(),
+        };
+        set.normalise();
+        set
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        std::iter::once(range).collect()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A part of a [`PiecewiseMap`]: values in `source` are moved so that `source.start` lands on `destination`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Segment<T> {
+    pub source: Range<T>,
+    pub destination: T,
+}
+
+/// Maps values by shifting ranges of them, like the maps of an almanac.
+/// Values outside of all segments map to themselves.
+///
+/// ```
+/// # use advent_of_code::interval::PiecewiseMap;
+/// let mut map = PiecewiseMap::new();
+/// map.insert(98..100, 50_u64);
+/// map.insert(50..98, 52);
+///
+/// assert_eq!(map.apply(99), 51);
+/// assert_eq!(map.apply(53), 55);
+/// assert_eq!(map.apply(10), 10);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PiecewiseMap<T> {
+    /// Sorted by source start, the sources do not overlap.
+    segments: Vec<Segment<T>>,
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> PiecewiseMap<T> {
+    pub fn new() -> Self {
+        Self { segments: vec![] }
+    }
+
+    pub fn segments(&self) -> &[Segment<T>] {
+        &self.segments
+    }
+
+    /// Maps `source` to the range starting at `destination`.
+    /// Values already covered by earlier segments keep their mapping.
+    pub fn insert(&mut self, source: Range<T>, destination: T) {
+        let free = IntervalSet::from(source.clone()).difference(&self.domain());
+
+        for piece in free.ranges() {
+            self.segments.push(Segment {
+                source: piece.clone(),
+                destination: destination + (piece.start - source.start),
+            });
+        }
+
+        self.segments
+            .sort_unstable_by_key(|segment| segment.source.start);
+    }
+
+    /// All values that are moved by a segment.
+    pub fn domain(&self) -> IntervalSet<T> {
+        self.segments
+            .iter()
+            .map(|segment| segment.source.clone())
+            .collect()
+    }
+
+    pub fn apply(&self, value: T) -> T {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.source.end <= value);
+
+        match self.segments.get(index) {
+            Some(segment) if segment.source.start <= value => {
+                segment.destination + (value - segment.source.start)
+            }
+            _ => value,
+        }
+    }
+
+    /// Maps every value of `set`.
+    pub fn apply_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.ranges()
+            .iter()
+            .flat_map(|range| self.split(range.clone()))
+            .map(|(piece, destination)| destination..destination + (piece.end - piece.start))
+            .collect()
+    }
+
+    /// Splits `range` into the pieces that are moved by the same segment (or not moved at all),
+    /// together with the value the start of each piece is mapped to.
+    fn split(&self, range: Range<T>) -> Vec<(Range<T>, T)> {
+        let mut pieces = vec![];
+        let mut start = range.start;
+
+        let first = self
+            .segments
+            .partition_point(|segment| segment.source.end <= start);
+
+        for segment in &self.segments[first..] {
+            if segment.source.start >= range.end {
+                break;
+            }
+
+            if start < segment.source.start {
+                pieces.push((start..segment.source.start, start));
+                start = segment.source.start;
+            }
+
+            let end = segment.source.end.min(range.end);
+            pieces.push((
+                start..end,
+                segment.destination + (start - segment.source.start),
+            ));
+            start = end;
+        }
+
+        if start < range.end {
+            pieces.push((start..range.end, start));
+        }
+
+        pieces
+    }
+
+    /// A single map that is equivalent to applying `self` and then `next`.
+    ///
+    /// ```
+    /// # use advent_of_code::interval::PiecewiseMap;
+    /// let mut double_shift = PiecewiseMap::new();
+    /// double_shift.insert(0..10, 5_u32);
+    /// let mut back = PiecewiseMap::new();
+    /// back.insert(5..15, 0);
+    ///
+    /// let composed = double_shift.then(&back);
+    /// assert_eq!(composed.apply(3), 3);
+    /// assert_eq!(composed.apply(12), 7);
+    /// ```
+    #[must_use]
+    pub fn then(&self, next: &Self) -> Self {
+        let mut segments = vec![];
+
+        // Values moved by `self` continue through `next`.
+        for segment in &self.segments {
+            let length = segment.source.end - segment.source.start;
+            let image = segment.destination..segment.destination + length;
+
+            for (piece, destination) in next.split(image) {
+                let source_start = segment.source.start + (piece.start - segment.destination);
+                segments.push(Segment {
+                    source: source_start..source_start + (piece.end - piece.start),
+                    destination,
+                });
+            }
+        }
+
+        // Values not moved by `self` are only moved by `next`.
+        let own_domain = self.domain();
+        for segment in &next.segments {
+            let untouched = IntervalSet::from(segment.source.clone()).difference(&own_domain);
+
+            for piece in untouched.ranges() {
+                segments.push(Segment {
+                    source: piece.clone(),
+                    destination: segment.destination + (piece.start - segment.source.start),
+                });
+            }
+        }
+
+        segments.retain(|segment| segment.source.start != segment.destination);
+        segments.sort_unstable_by_key(|segment| segment.source.start);
+
+        Self { segments }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{IntervalSet, PiecewiseMap};
+
+    #[test]
+    fn normalises() {
+        let set: IntervalSet<i32> = [5..8, 1..3, 3..4, 10..10, 7..9].into_iter().collect();
+        assert_eq!(set.ranges(), &[1..4, 5..9]);
+        assert_eq!(set.size(), 7);
+        assert_eq!(set.min(), Some(1));
+        assert!(set.contains(3));
+        assert!(!set.contains(4));
+        assert!(!set.contains(9));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: IntervalSet<i32> = [0..10, 20..30].into_iter().collect();
+        let b: IntervalSet<i32> = [5..25, 28..29].into_iter().collect();
+
+        assert_eq!(a.union(&b).ranges(), &[0..30]);
+        assert_eq!(a.intersection(&b).ranges(), &[5..10, 20..25, 28..29]);
+        assert_eq!(a.difference(&b).ranges(), &[0..5, 25..28, 29..30]);
+        assert_eq!(b.difference(&a).ranges(), &[10..20]);
+        assert!(a.difference(&a).is_empty());
+    }
+
+    fn almanac_map(lines: &[(u64, u64, u64)]) -> PiecewiseMap<u64> {
+        let mut map = PiecewiseMap::new();
+        for &(destination, source, length) in lines {
+            map.insert(source..source + length, destination);
+        }
+        map
+    }
+
+    #[test]
+    fn maps_sets() {
+        let seed_to_soil = almanac_map(&[(50, 98, 2), (52, 50, 48)]);
+
+        let seeds: IntervalSet<u64> = [79..93, 55..68].into_iter().collect();
+        let soil = seed_to_soil.apply_set(&seeds);
+
+        assert_eq!(soil.ranges(), &[57..70, 81..95]);
+
+        let edge = seed_to_soil.apply_set(&IntervalSet::from(96..101));
+        // 96..98 moves to 98..100, which touches the unmapped 100..101.
+        assert_eq!(edge.ranges(), &[50..52, 98..101]);
+    }
+
+    #[test]
+    fn composition_matches_applying_twice() {
+        let first = almanac_map(&[(50, 98, 2), (52, 50, 48)]);
+        let second = almanac_map(&[(0, 15, 37), (37, 52, 2), (39, 0, 15)]);
+        let composed = first.then(&second);
+
+        for value in 0..120 {
+            assert_eq!(composed.apply(value), second.apply(first.apply(value)));
+        }
+    }
+
+    #[test]
+    fn earlier_segments_win() {
+        let mut map = PiecewiseMap::new();
+        map.insert(10..20, 100_u32);
+        map.insert(5..15, 200);
+
+        assert_eq!(map.apply(12), 102);
+        assert_eq!(map.apply(7), 202);
+        assert_eq!(map.segments().len(), 2);
+    }
+}
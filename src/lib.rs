@@ -1,4 +1,17 @@
 mod day;
+pub mod bitset;
+pub mod cycle;
+pub mod dot;
+pub mod geometry;
+pub mod grid;
+pub mod interval;
+pub mod matcher;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod scan;
+pub mod search;
 pub mod template;
+pub mod viz;
 
 pub use day::*;
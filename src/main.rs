@@ -1,9 +1,11 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve};
+use advent_of_code::template::commands::{all, download, read, scaffold, solve, CommandError};
+use advent_of_code::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 use args::{parse, AppArguments};
 
 mod args {
     use std::process;
 
+    use advent_of_code::viz;
     use advent_of_code::Day;
 
     pub enum AppArguments {
@@ -21,6 +23,11 @@ mod args {
             release: bool,
             time: bool,
             submit: Option<u8>,
+            variants: bool,
+            verbose: bool,
+            visualize: Option<viz::Options>,
+            /// Arguments after `--`, for the commands of the day, e.g. `--explain`.
+            extra: Vec<String>,
         },
         All {
             release: bool,
@@ -29,7 +36,12 @@ mod args {
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
-        let mut args = pico_args::Arguments::from_env();
+        let mut args: Vec<_> = std::env::args_os().skip(1).collect();
+        let forwarded = match args.iter().position(|arg| arg == "--") {
+            Some(separator) => args.split_off(separator).split_off(1),
+            None => vec![],
+        };
+        let mut args = pico_args::Arguments::from_vec(args);
 
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
@@ -50,18 +62,28 @@ mod args {
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 time: args.contains("--time"),
+                variants: args.contains("--variants"),
+                verbose: args.contains("--verbose"),
+                visualize: viz::Options::from_args(&mut args)?,
+                extra: forwarded
+                    .iter()
+                    .map(|arg| arg.to_string_lossy().into_owned())
+                    .collect(),
             },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
-                process::exit(1);
+                process::exit(2);
             }
             None => {
                 eprintln!("No command specified.");
-                process::exit(1);
+                process::exit(2);
             }
         };
 
-        let remaining = args.finish();
+        let mut remaining = args.finish();
+        if !matches!(app_args, AppArguments::Solve { .. }) {
+            remaining.extend(forwarded);
+        }
         if !remaining.is_empty() {
             eprintln!("Warning: unknown argument(s): {remaining:?}.");
         }
@@ -70,23 +92,87 @@ mod args {
     }
 }
 
+/// Formats the reports returned by the template commands.
+mod output {
+    use super::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+    use advent_of_code::template::commands::{all, download, scaffold};
+
+    pub fn scaffold(report: &scaffold::ScaffoldReport) {
+        println!("Created module file \"{}\"", report.module_path);
+        println!("Created empty input file \"{}\"", report.input_path);
+        println!("Created empty example file \"{}\"", report.example_path);
+        println!("---");
+        println!("🎄 Type `cargo solve {}` to run your solution.", report.day);
+    }
+
+    pub fn download(report: &download::DownloadReport) {
+        println!("---");
+        println!("🎄 Successfully wrote input to \"{}\".", report.input_path);
+        println!(
+            "🎄 Successfully wrote puzzle to \"{}\".",
+            report.puzzle_path
+        );
+    }
+
+    pub fn all(report: &all::AllReport) {
+        if let Some(total_millis) = report.total_millis {
+            println!(
+                "\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
+            );
+        }
+
+        match &report.readme_update {
+            Some(Ok(())) => println!("Successfully updated README with benchmarks."),
+            Some(Err(e)) => eprintln!("Failed to update readme with benchmarks: {e}"),
+            None => {}
+        }
+    }
+}
+
 fn main() {
-    match parse() {
+    let args = match parse() {
         Err(err) => {
             eprintln!("Error: {err}");
-            std::process::exit(1);
+            std::process::exit(2);
         }
-        Ok(args) => match args {
-            AppArguments::All { release, time } => all::handle(release, time),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
-            AppArguments::Scaffold { day } => scaffold::handle(day),
-            AppArguments::Solve {
-                day,
-                release,
-                time,
-                submit,
-            } => solve::handle(day, release, time, submit),
-        },
+        Ok(args) => args,
+    };
+
+    let result: Result<(), CommandError> = match args {
+        AppArguments::All { release, time } => {
+            all::run(release, time, true).map(|report| output::all(&report))
+        }
+        AppArguments::Download { day } => {
+            download::run(day).map(|report| output::download(&report))
+        }
+        AppArguments::Read { day } => read::run(day).map(|_| ()),
+        AppArguments::Scaffold { day } => {
+            scaffold::run(day).map(|report| output::scaffold(&report))
+        }
+        AppArguments::Solve {
+            day,
+            release,
+            time,
+            submit,
+            variants,
+            verbose,
+            visualize,
+            extra,
+        } => solve::run(
+            day,
+            release,
+            time,
+            submit,
+            variants,
+            verbose,
+            visualize.as_ref(),
+            &extra,
+        )
+        .map(|_| ()),
     };
+
+    if let Err(e) = result {
+        eprintln!("Error: {e}");
+        std::process::exit(e.exit_code());
+    }
 }
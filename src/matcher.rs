@@ -0,0 +1,258 @@
+/// Finds many patterns in a text at once, in a single pass.
+///
+/// [`Matcher`] is an Aho–Corasick automaton: a trie of all patterns where every node also links to
+/// the longest suffix of its text that is in the trie, so the scan never has to go back.
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+const ROOT: usize = 0;
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Sorted by byte.
+    edges: Vec<(u8, usize)>,
+    /// The node of the longest proper suffix of this node's text.
+    fail: usize,
+    /// The patterns that end at this node, including those ending at its suffixes.
+    outputs: Vec<usize>,
+}
+
+impl Node {
+    fn edge(&self, byte: u8) -> Option<usize> {
+        self.edges
+            .binary_search_by_key(&byte, |&(b, _)| b)
+            .ok()
+            .map(|i| self.edges[i].1)
+    }
+}
+
+/// An occurrence of a pattern in a text, as found by [`Matcher`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct Match<'a, V> {
+    /// Byte offset of the first byte of the match.
+    pub start: usize,
+    /// Byte offset after the last byte of the match.
+    pub end: usize,
+    /// The value the pattern was registered with.
+    pub value: &'a V,
+}
+
+// Derived `Clone` and `Copy` would require `V: Copy`, but only a reference is copied.
+impl<V> Clone for Match<'_, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for Match<'_, V> {}
+
+/// A set of patterns, each with a value, that are searched for together.
+///
+/// Matches may overlap, e.g. both `eight` and `two` are found in `eightwo`.
+///
+/// ```
+/// # use advent_of_code::matcher::Matcher;
+/// let matcher = Matcher::new([("one", 1), ("eight", 8), ("two", 2), ("1", 1)]);
+///
+/// let values: Vec<_> = matcher.find_iter("xeightwone1").map(|m| *m.value).collect();
+/// assert_eq!(values, vec![8, 2, 1, 1]);
+///
+/// let (first, last) = matcher.first_and_last("1eightwo").unwrap();
+/// assert_eq!((*first.value, *last.value), (1, 2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Matcher<V> {
+    nodes: Vec<Node>,
+    /// The length and value of every pattern.
+    patterns: Vec<(usize, V)>,
+}
+
+impl<V> Matcher<V> {
+    /// Builds the automaton. Empty patterns never match.
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = (P, V)>) -> Self {
+        let mut matcher = Self {
+            nodes: vec![Node::default()],
+            patterns: vec![],
+        };
+
+        for (pattern, value) in patterns {
+            let pattern = pattern.as_ref();
+            let id = matcher.patterns.len();
+            matcher.patterns.push((pattern.len(), value));
+
+            if !pattern.is_empty() {
+                let node = matcher.insert(pattern);
+                matcher.nodes[node].outputs.push(id);
+            }
+        }
+
+        matcher.link();
+        matcher
+    }
+
+    /// Adds the trie nodes for `pattern`, returns the node it ends at.
+    fn insert(&mut self, pattern: &[u8]) -> usize {
+        let mut node = ROOT;
+
+        for &byte in pattern {
+            node = match self.nodes[node].edge(byte) {
+                Some(next) => next,
+                None => {
+                    let next = self.nodes.len();
+                    self.nodes.push(Node::default());
+
+                    let edges = &mut self.nodes[node].edges;
+                    let at = edges.partition_point(|&(b, _)| b < byte);
+                    edges.insert(at, (byte, next));
+
+                    next
+                }
+            };
+        }
+
+        node
+    }
+
+    /// Sets the suffix links, visiting nodes by depth so that shorter suffixes are linked first.
+    fn link(&mut self) {
+        let mut queue: VecDeque<usize> = self.nodes[ROOT].edges.iter().map(|&(_, n)| n).collect();
+
+        while let Some(node) = queue.pop_front() {
+            for (byte, child) in self.nodes[node].edges.clone() {
+                let mut fail = self.nodes[node].fail;
+                let suffix = loop {
+                    match self.nodes[fail].edge(byte) {
+                        Some(next) => break next,
+                        None if fail == ROOT => break ROOT,
+                        None => fail = self.nodes[fail].fail,
+                    }
+                };
+
+                self.nodes[child].fail = suffix;
+
+                let inherited = self.nodes[suffix].outputs.clone();
+                self.nodes[child].outputs.extend(inherited);
+
+                queue.push_back(child);
+            }
+        }
+    }
+
+    /// The amount of patterns.
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// The node after reading `byte` in `node`.
+    fn step(&self, mut node: usize, byte: u8) -> usize {
+        loop {
+            if let Some(next) = self.nodes[node].edge(byte) {
+                return next;
+            }
+            if node == ROOT {
+                return ROOT;
+            }
+            node = self.nodes[node].fail;
+        }
+    }
+
+    /// All matches, including overlapping ones, ordered by their end.
+    /// Matches with the same end are ordered longest first.
+    pub fn find_iter<'a, H>(&'a self, haystack: &'a H) -> impl Iterator<Item = Match<'a, V>> + 'a
+    where
+        H: AsRef<[u8]> + ?Sized,
+    {
+        let mut node = ROOT;
+
+        haystack
+            .as_ref()
+            .iter()
+            .enumerate()
+            .flat_map(move |(i, &byte)| {
+                node = self.step(node, byte);
+
+                self.nodes[node].outputs.iter().map(move |&id| {
+                    let (length, value) = &self.patterns[id];
+                    Match {
+                        start: i + 1 - length,
+                        end: i + 1,
+                        value,
+                    }
+                })
+            })
+    }
+
+    /// The match that starts first and the match that starts last, found in one pass.
+    /// Of matches with the same start, the longest one is picked.
+    pub fn first_and_last<'a, H>(&'a self, haystack: &'a H) -> Option<(Match<'a, V>, Match<'a, V>)>
+    where
+        H: AsRef<[u8]> + ?Sized,
+    {
+        let first_key = |m: &Match<V>| (m.start, Reverse(m.end));
+        let last_key = |m: &Match<V>| (m.start, m.end);
+
+        self.find_iter(haystack).fold(None, |found, m| match found {
+            None => Some((m, m)),
+            Some((first, last)) => Some((
+                if first_key(&m) < first_key(&first) {
+                    m
+                } else {
+                    first
+                },
+                if last_key(&m) > last_key(&last) {
+                    m
+                } else {
+                    last
+                },
+            )),
+        })
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Matcher;
+
+    #[test]
+    fn overlapping_matches() {
+        let matcher = Matcher::new([("he", 0), ("she", 1), ("his", 2), ("hers", 3)]);
+
+        let found: Vec<_> = matcher
+            .find_iter("ushers")
+            .map(|m| (m.start, m.end, *m.value))
+            .collect();
+
+        assert_eq!(found, vec![(1, 4, 1), (2, 4, 0), (2, 6, 3)]);
+    }
+
+    #[test]
+    fn first_and_last() {
+        let matcher = Matcher::new([("bc", 'x'), ("abcd", 'y'), ("d", 'z'), ("cd", 'w')]);
+
+        let (first, last) = matcher.first_and_last("abcd").unwrap();
+        assert_eq!((first.start, *first.value), (0, 'y'));
+        assert_eq!((last.start, *last.value), (3, 'z'));
+
+        assert!(matcher.first_and_last("xyz").is_none());
+    }
+
+    #[test]
+    fn edge_cases() {
+        let matcher = Matcher::new([("", 0), ("aa", 1), ("aa", 2)]);
+        assert_eq!(matcher.len(), 3);
+
+        let values: Vec<_> = matcher
+            .find_iter("aaa")
+            .map(|m| (m.start, *m.value))
+            .collect();
+        assert_eq!(values, vec![(0, 1), (0, 2), (1, 1), (1, 2)]);
+
+        let empty: Matcher<()> = Matcher::new(Vec::<(&str, ())>::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.find_iter("abc").count(), 0);
+    }
+}
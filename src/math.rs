@@ -0,0 +1,550 @@
+/// Exact integer math: divisibility, modular arithmetic, roots and polynomials.
+///
+/// Functions that can overflow return [`None`] instead of wrapping or panicking.
+/// Intermediate results are computed in `i128`, so results are exact for every width up to 64 bits.
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+/// The primitive integer types.
+pub trait Integer:
+    Copy
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    /// The absolute value, [`None`] for the minimum of a signed type.
+    fn checked_abs(self) -> Option<Self>;
+    fn to_i128(self) -> Option<i128>;
+    fn from_i128(value: i128) -> Option<Self>;
+    fn to_u128(self) -> Option<u128>;
+    fn from_u128(value: u128) -> Option<Self>;
+}
+
+/// The signed primitive integer types.
+pub trait Signed: Integer {}
+
+macro_rules! impl_integer {
+    ($($t:ty),* ; abs: $abs:ident) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn checked_abs(self) -> Option<Self> {
+                    impl_integer!(@abs $abs self)
+                }
+
+                fn to_i128(self) -> Option<i128> {
+                    i128::try_from(self).ok()
+                }
+
+                fn from_i128(value: i128) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+
+                fn to_u128(self) -> Option<u128> {
+                    u128::try_from(self).ok()
+                }
+
+                fn from_u128(value: u128) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+            }
+        )*
+    };
+    (@abs signed $value:ident) => { $value.checked_abs() };
+    (@abs unsigned $value:ident) => { Some($value) };
+}
+
+impl_integer!(i8, i16, i32, i64, i128, isize; abs: signed);
+impl_integer!(u8, u16, u32, u64, u128, usize; abs: unsigned);
+
+impl Signed for i8 {}
+impl Signed for i16 {}
+impl Signed for i32 {}
+impl Signed for i64 {}
+impl Signed for i128 {}
+impl Signed for isize {}
+
+/// The greatest common divisor, always non-negative. `gcd(0, 0)` is `0`.
+/// [`None`] if the result does not fit, which only happens for `gcd(MIN, 0)` and `gcd(MIN, MIN)` of a signed type.
+///
+/// ```
+/// # use advent_of_code::math::gcd;
+/// assert_eq!(gcd(54_u64, 24), Some(6));
+/// assert_eq!(gcd(i8::MIN, 0), None);
+/// ```
+pub fn gcd<T: Integer>(a: T, b: T) -> Option<T> {
+    let abs = |n: T| n.checked_abs();
+
+    match (abs(a), abs(b)) {
+        (Some(mut a), Some(mut b)) => {
+            while b != T::ZERO {
+                (a, b) = (b, a % b);
+            }
+            Some(a)
+        }
+        // `MIN` is a power of two, so reduce it first by the other, representable value.
+        (None, Some(b)) if b != T::ZERO => gcd(b, a % b),
+        (Some(a), None) if a != T::ZERO => gcd(a, b % a),
+        _ => None,
+    }
+}
+
+/// The least common multiple, always non-negative. [`None`] if it overflows.
+///
+/// ```
+/// # use advent_of_code::math::lcm;
+/// assert_eq!(lcm(4_u8, 6), Some(12));
+/// assert_eq!(lcm(200_u8, 3), None);
+/// assert_eq!(lcm(i32::MIN, i32::MIN), None);
+/// ```
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+
+    (a / gcd(a, b)?).checked_mul(b)?.checked_abs()
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y == g`, where `g` is the non-negative [`gcd`] of `a` and `b`.
+/// [`None`] if a coefficient overflows.
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> Option<(T, T, T)> {
+    let (g, x, y) = extended_gcd_i128(a.to_i128()?, b.to_i128()?)?;
+    Some((T::from_i128(g)?, T::from_i128(x)?, T::from_i128(y)?))
+}
+
+fn extended_gcd_i128(a: i128, b: i128) -> Option<(i128, i128, i128)> {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1_i128, 0_i128);
+    let (mut old_t, mut t) = (0_i128, 1_i128);
+
+    while r != 0 {
+        let q = old_r.checked_div(r)?;
+        (old_r, r) = (r, old_r.checked_sub(q.checked_mul(r)?)?);
+        (old_s, s) = (s, old_s.checked_sub(q.checked_mul(s)?)?);
+        (old_t, t) = (t, old_t.checked_sub(q.checked_mul(t)?)?);
+    }
+
+    if old_r < 0 {
+        return Some((
+            old_r.checked_neg()?,
+            old_s.checked_neg()?,
+            old_t.checked_neg()?,
+        ));
+    }
+
+    Some((old_r, old_s, old_t))
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`.
+/// [`None`] if `a` and `modulus` are not coprime or `modulus` is not positive.
+pub fn mod_inverse<T: Integer>(a: T, modulus: T) -> Option<T> {
+    let modulus = modulus.to_i128()?;
+    if modulus <= 0 {
+        return None;
+    }
+
+    let (g, x, _) = extended_gcd_i128(a.to_i128()?.rem_euclid(modulus), modulus)?;
+    if g != 1 {
+        return None;
+    }
+
+    T::from_i128(x.rem_euclid(modulus))
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)` with the Chinese remainder theorem.
+/// The moduli do not have to be coprime.
+///
+/// Returns `(x, m)`: every solution is `x + k * m`, with `x` in `0..m` and `m` the [`lcm`] of the moduli.
+/// [`None`] if the congruences contradict each other, a modulus is not positive or `m` overflows.
+///
+/// ```
+/// # use advent_of_code::math::crt;
+/// assert_eq!(crt(&[(2_u32, 3), (3, 5), (2, 7)]), Some((23, 105)));
+/// assert_eq!(crt(&[(1_i64, 4), (3, 6)]), Some((9, 12)));
+/// assert_eq!(crt(&[(0_i64, 4), (1, 6)]), None);
+/// ```
+pub fn crt<T: Integer>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let (mut x, mut m) = (0_i128, 1_i128);
+
+    for &(residue, modulus) in congruences {
+        let modulus = modulus.to_i128()?;
+        if modulus <= 0 {
+            return None;
+        }
+        let residue = residue.to_i128()?.rem_euclid(modulus);
+
+        // Find k with x + k * m ≡ residue (mod modulus), i.e. k * m ≡ residue - x.
+        let (g, inverse, _) = extended_gcd_i128(m, modulus)?;
+        let difference = residue.checked_sub(x)?;
+        if difference % g != 0 {
+            return None;
+        }
+
+        let step = modulus / g;
+        let k = mul_mod(difference / g, inverse, step);
+
+        x = x.checked_add(m.checked_mul(k)?)?;
+        m = m.checked_mul(step)?;
+        x = x.rem_euclid(m);
+    }
+
+    Some((T::from_i128(x)?, T::from_i128(m)?))
+}
+
+/// `a * b mod modulus` in `0..modulus`, without overflowing for any positive modulus.
+fn mul_mod(a: i128, b: i128, modulus: i128) -> i128 {
+    let (mut a, mut b) = (a.rem_euclid(modulus) as u128, b.rem_euclid(modulus) as u128);
+    let modulus = modulus as u128;
+
+    if let Some(product) = a.checked_mul(b) {
+        return (product % modulus) as i128;
+    }
+
+    // Double and add, every sum stays below `2 * modulus`, which fits 128 unsigned bits.
+    let mut product = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            product = (product + a) % modulus;
+        }
+        a = (a * 2) % modulus;
+        b >>= 1;
+    }
+
+    product as i128
+}
+
+/// The integer square root, rounded down. [`None`] for negative numbers.
+///
+/// ```
+/// # use advent_of_code::math::isqrt;
+/// assert_eq!(isqrt(24_u64), Some(4));
+/// assert_eq!(isqrt(u64::MAX), Some(u32::MAX as u64));
+/// assert_eq!(isqrt(-1_i32), None);
+/// ```
+pub fn isqrt<T: Integer>(n: T) -> Option<T> {
+    // Negative numbers are the only ones that do not fit an u128.
+    T::from_u128(n.to_u128()?.isqrt())
+}
+
+/// All integers `x` with `a * x² + b * x + c < 0`, for a positive `a`.
+/// The range is empty if there are none. [`None`] if the coefficients are out of range or `a` is not positive.
+///
+/// Exact for all coefficients that fit 64 bits, e.g. `x² - t * x + d` for any `u64` values of `t` and `d`.
+///
+/// ```
+/// # use advent_of_code::math::quadratic_negative;
+/// // x² - 7x + 10 < 0 for 2 < x < 5
+/// assert_eq!(quadratic_negative(1_i64, -7, 10), Some(3..=4));
+/// assert!(quadratic_negative(1_i64, 0, 1).unwrap().is_empty());
+/// ```
+pub fn quadratic_negative<T: Integer>(a: T, b: T, c: T) -> Option<RangeInclusive<T>> {
+    let (a, b, c) = (a.to_i128()?, b.to_i128()?, c.to_i128()?);
+    if a <= 0 {
+        return None;
+    }
+
+    // f(x) = x * (a * x + b) + c. Only the inner product has to fit, the outer one is compared in 256 bits.
+    let is_negative = |x: i128| -> Option<bool> {
+        let inner = a.checked_mul(x)?.checked_add(b)?;
+        Some(product_plus_sign(x, inner, c) == Ordering::Less)
+    };
+
+    // The integer minimum lies next to the vertex at -b / 2a.
+    let vertex = b.checked_neg()?.div_euclid(a.checked_mul(2)?);
+    let middle = if is_negative(vertex)? {
+        vertex
+    } else if is_negative(vertex + 1)? {
+        vertex + 1
+    } else {
+        return Some(T::ONE..=T::ZERO);
+    };
+
+    // The first integer that is not negative when stepping away from the middle.
+    let edge = |direction: i128| -> Option<i128> {
+        let mut inside = 0_i128;
+        let mut outside = 1_i128;
+        while is_negative(middle.checked_add(direction.checked_mul(outside)?)?)? {
+            inside = outside;
+            outside = outside.checked_mul(2)?;
+        }
+
+        while outside - inside > 1 {
+            let mid = inside + (outside - inside) / 2;
+            if is_negative(middle + direction * mid)? {
+                inside = mid;
+            } else {
+                outside = mid;
+            }
+        }
+
+        Some(middle + direction * inside)
+    };
+
+    Some(T::from_i128(edge(-1)?)?..=T::from_i128(edge(1)?)?)
+}
+
+/// The sign of `x * y + c`, without overflowing.
+fn product_plus_sign(x: i128, y: i128, c: i128) -> Ordering {
+    let product = (x.signum() * y.signum()).cmp(&0);
+
+    if product == Ordering::Equal || c == 0 || product == c.cmp(&0) {
+        return if product == Ordering::Equal {
+            c.cmp(&0)
+        } else {
+            product
+        };
+    }
+
+    // Opposite signs, the larger magnitude decides.
+    match widening_mul(x.unsigned_abs(), y.unsigned_abs()).cmp(&(0, c.unsigned_abs())) {
+        Ordering::Greater => product,
+        Ordering::Less => c.cmp(&0),
+        Ordering::Equal => Ordering::Equal,
+    }
+}
+
+/// The full product of `a` and `b` as `(high, low)` halves.
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & MASK);
+    let (b_high, b_low) = (b >> 64, b & MASK);
+
+    let low = a_low * b_low;
+    let cross_1 = a_high * b_low;
+    let cross_2 = a_low * b_high;
+
+    let middle = (low >> 64) + (cross_1 & MASK) + (cross_2 & MASK);
+    let low = (low & MASK) | (middle << 64);
+    let high = a_high * b_high + (cross_1 >> 64) + (cross_2 >> 64) + (middle >> 64);
+
+    (high, low)
+}
+
+/// The binomial coefficient "`n` choose `k`". [`None`] if it overflows or `n` is negative.
+///
+/// ```
+/// # use advent_of_code::math::binomial;
+/// assert_eq!(binomial(5_u32, 2), Some(10));
+/// assert_eq!(binomial(3_u32, 4), Some(0));
+/// assert_eq!(binomial(40_u8, 20), None);
+/// ```
+pub fn binomial<T: Integer>(n: T, k: T) -> Option<T> {
+    let (n, k) = (n.to_i128()?, k.to_i128()?);
+    if n < 0 {
+        return None;
+    }
+    if k < 0 || k > n {
+        return Some(T::ZERO);
+    }
+
+    T::from_i128(falling_binomial(n, k.min(n - k))?)
+}
+
+/// "`x` choose `k`" for any integer `x`, e.g. `(-2 choose 3) == -4`.
+/// Each intermediate product of `i` consecutive integers is divisible by `i!`, so the divisions are exact.
+fn falling_binomial(x: i128, k: i128) -> Option<i128> {
+    let mut result = 1_i128;
+
+    for i in 0..k {
+        result = result.checked_mul(x.checked_sub(i)?)? / (i + 1);
+    }
+
+    Some(result)
+}
+
+/// Evaluates the lowest degree polynomial through the points `(i, samples[i])` at `x`.
+/// `x` may lie before, inside or after the samples, e.g. `samples.len()` predicts the next value.
+/// [`None`] for no samples, or if a value overflows.
+///
+/// ```
+/// # use advent_of_code::math::extrapolate;
+/// let squares = [0_i64, 1, 4, 9];
+/// assert_eq!(extrapolate(&squares, 4), Some(16));
+/// assert_eq!(extrapolate(&squares, -3), Some(9));
+/// ```
+pub fn extrapolate<T: Integer>(samples: &[T], x: i64) -> Option<T> {
+    // Newton's forward differences: p(x) = Σ Δᵏy₀ · (x choose k)
+    let mut row: Vec<i128> = samples
+        .iter()
+        .map(|sample| sample.to_i128())
+        .collect::<Option<_>>()?;
+
+    if row.is_empty() {
+        return None;
+    }
+
+    let x = i128::from(x);
+    let mut value = 0_i128;
+
+    for k in 0.. {
+        let Some(&leading) = row.first() else {
+            break;
+        };
+
+        if leading != 0 {
+            value = value.checked_add(leading.checked_mul(falling_binomial(x, k)?)?)?;
+        }
+
+        row = row
+            .windows(2)
+            .map(|pair| pair[1].checked_sub(pair[0]))
+            .collect::<Option<_>>()?;
+    }
+
+    T::from_i128(value)
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        binomial, crt, extended_gcd, extrapolate, gcd, isqrt, lcm, mod_inverse, quadratic_negative,
+    };
+
+    #[test]
+    fn divisibility() {
+        assert_eq!(gcd(54_u64, 24), Some(6));
+        assert_eq!(gcd(-54_i32, 24), Some(6));
+        assert_eq!(gcd(0_u8, 7), Some(7));
+        assert_eq!(gcd(0_u8, 0), Some(0));
+        assert_eq!(gcd(i8::MIN, 6), Some(2));
+        assert_eq!(gcd(i8::MIN, -64), Some(64));
+        assert_eq!(gcd(i8::MIN, 0), None);
+        assert_eq!(gcd(i8::MIN, i8::MIN), None);
+
+        assert_eq!(lcm(21_u64, 6), Some(42));
+        assert_eq!(lcm(-4_i32, 6), Some(12));
+        assert_eq!(lcm(0_u32, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, 2), None);
+        assert_eq!(lcm(i32::MIN, i32::MIN), None);
+        assert_eq!(lcm(i32::MIN, 1), None);
+    }
+
+    #[test]
+    fn modular() {
+        let (g, x, y) = extended_gcd(240_i64, 46).unwrap();
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+
+        assert_eq!(mod_inverse(3_u32, 11), Some(4));
+        assert_eq!(mod_inverse(-3_i32, 11), Some(7));
+        assert_eq!(mod_inverse(4_u32, 8), None);
+
+        assert_eq!(crt::<u64>(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(3_u64, 4), (5, 6), (7, 10)]), Some((47, 60)));
+
+        // Moduli around 2⁶¹ whose product needs more than 64 bits.
+        let (a, b) = (
+            2_305_843_009_213_693_951_i128,
+            2_305_843_009_213_693_921_i128,
+        );
+        let (x, m) = crt(&[(1, a), (2, b)]).unwrap();
+        assert_eq!(m, a * b);
+        assert_eq!((x % a, x % b), (1, 2));
+        assert_eq!(crt(&[(1_u64, u64::MAX), (0, u64::MAX - 1)]), None);
+
+        // Residues near 2¹⁰⁰, whose product needs more than 128 bits.
+        let b = (1_i128 << 100) + 1;
+        let (x, m) = crt(&[(1, 3), (1_i128 << 99, b)]).unwrap();
+        assert_eq!(m, 3 * b);
+        assert_eq!((x % 3, x % b), (1, 1 << 99));
+    }
+
+    #[test]
+    fn roots() {
+        for n in 0_u32..1000 {
+            let root = isqrt(n).unwrap();
+            assert!(root * root <= n && (root + 1) * (root + 1) > n);
+        }
+        assert_eq!(isqrt(u128::MAX), Some(u64::MAX as u128));
+        assert_eq!(isqrt(i64::MAX), Some(3_037_000_499));
+    }
+
+    #[test]
+    fn quadratics() {
+        // Holding a button for h of t ms beats d when h * (t - h) > d, i.e. h² - th + d < 0.
+        let brute_force = |t: i64, d: i64| (0..=t).filter(|h| h * (t - h) > d).count();
+
+        for t in 0..40 {
+            for d in 0..200 {
+                let range = quadratic_negative(1, -t, d).unwrap();
+                assert_eq!(range.count(), brute_force(t, d), "t = {t}, d = {d}");
+            }
+        }
+
+        let range = quadratic_negative(1_i64, -53_897_698, 313_109_012_141_201).unwrap();
+        assert_eq!(range.end() - range.start() + 1, 40_651_271);
+
+        assert_eq!(quadratic_negative(0_i64, 1, 1), None);
+        assert_eq!(quadratic_negative(2_i64, 0, -9), Some(-2..=2));
+        assert_eq!(quadratic_negative(3_i64, 5, -2), Some(-1..=0));
+
+        for a in 1_i64..5 {
+            for b in -30..30 {
+                for c in -30..30 {
+                    let negative: Vec<i64> =
+                        (-40..40).filter(|x| a * x * x + b * x + c < 0).collect();
+                    let range = quadratic_negative(a, b, c).unwrap();
+                    assert_eq!(range.collect::<Vec<_>>(), negative, "{a}x² + {b}x + {c}");
+                }
+            }
+        }
+
+        // Full-range u64 races, where b² no longer fits an i128.
+        let max = i128::from(u64::MAX);
+        assert_eq!(quadratic_negative(1, -max, 0), Some(1..=max - 1));
+        let range = quadratic_negative(1, -(1_i128 << 33), max).unwrap();
+        assert_eq!(range, 1 << 32..=1 << 32);
+        assert!(quadratic_negative(1, -(1_i128 << 33), max + 1)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            quadratic_negative(
+                i128::from(i64::MAX),
+                i128::from(i64::MIN),
+                i128::from(i64::MIN)
+            ),
+            Some(0..=1)
+        );
+        assert_eq!(quadratic_negative(1, i128::MIN, 0), None);
+    }
+
+    #[test]
+    fn polynomials() {
+        assert_eq!(binomial(0_u64, 0), Some(1));
+        assert_eq!(binomial(66_u64, 33), Some(7_219_428_434_016_265_740));
+        assert_eq!(binomial(68_u64, 34), None);
+
+        let samples = [10_i64, 13, 16, 21, 30, 45];
+        assert_eq!(extrapolate(&samples, 6), Some(68));
+        assert_eq!(extrapolate(&samples, -1), Some(5));
+        assert_eq!(extrapolate(&[7_u8], 100), Some(7));
+        assert_eq!(extrapolate::<i32>(&[], 0), None);
+        assert_eq!(extrapolate(&[0_u8, 100, 200], 3), None);
+    }
+}
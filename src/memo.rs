@@ -0,0 +1,308 @@
+/// Memoization for recursive solutions.
+///
+/// Statistics of every [`Memo`] are collected while the runner executes a part with `--verbose`.
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    static RECORDER: RefCell<Vec<MemoStats>> = const { RefCell::new(vec![]) };
+}
+
+/// Where a [`Memo`] keeps its values.
+pub trait Storage<K, V> {
+    fn get(&self, key: &K) -> Option<&V>;
+    fn insert(&mut self, key: K, value: V);
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Eq + Hash, V> Storage<K, V> for HashMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+/// Array backed storage for states that map to an index in `0..len`.
+#[derive(Debug, Clone)]
+pub struct Dense<V> {
+    values: Vec<Option<V>>,
+    len: usize,
+}
+
+impl<V> Storage<usize, V> for Dense<V> {
+    fn get(&self, key: &usize) -> Option<&V> {
+        self.values[*key].as_ref()
+    }
+
+    fn insert(&mut self, key: usize, value: V) {
+        if self.values[key].replace(value).is_none() {
+            self.len += 1;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// A cache of computed values, with hit and miss counts.
+///
+/// Keys may borrow from the input, e.g. `(&str, &[usize])`, so looking up a state does not require cloning it.
+///
+/// ```
+/// # use advent_of_code::memo::Memo;
+/// fn fib(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+///     memo.get_or_compute(n, |memo, &n| match n {
+///         0 | 1 => n,
+///         _ => fib(memo, n - 1) + fib(memo, n - 2),
+///     })
+/// }
+///
+/// assert_eq!(fib(&mut Memo::new(), 90), 2880067194370816120);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Memo<K, V, S: Storage<K, V> = HashMap<K, V>> {
+    storage: S,
+    name: &'static str,
+    hits: u64,
+    misses: u64,
+    _marker: std::marker::PhantomData<(K, V)>,
+}
+
+impl<K: Eq + Hash, V> Memo<K, V> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_storage(HashMap::new())
+    }
+}
+
+impl<K: Eq + Hash, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Memo<usize, V, Dense<V>> {
+    /// A memo for keys in `0..len`, backed by an array instead of a hash map.
+    #[must_use]
+    pub fn dense(len: usize) -> Self {
+        Self::with_storage(Dense {
+            values: std::iter::repeat_with(|| None).take(len).collect(),
+            len: 0,
+        })
+    }
+}
+
+impl<K, V, S: Storage<K, V>> Memo<K, V, S> {
+    pub fn with_storage(storage: S) -> Self {
+        Self {
+            storage,
+            name: "memo",
+            hits: 0,
+            misses: 0,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Sets the name the statistics of this memo are reported under.
+    #[must_use]
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Returns the cached value for `key`, or computes and caches it.
+    /// `compute` receives the memo itself, so it can recurse into [`Memo::get_or_compute`].
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self, &K) -> V) -> V
+    where
+        V: Clone,
+    {
+        if let Some(value) = self.storage.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+
+        self.misses += 1;
+
+        let value = compute(self, &key);
+        self.storage.insert(key, value.clone());
+        value
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.storage.get(key)
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        MemoStats {
+            name: self.name,
+            instances: 1,
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.storage.len() as u64,
+        }
+    }
+}
+
+impl<K, V, S: Storage<K, V>> Drop for Memo<K, V, S> {
+    fn drop(&mut self) {
+        if !ENABLED.with(Cell::get) {
+            return;
+        }
+
+        let stats = self.stats();
+
+        RECORDER.with(|recorder| {
+            let mut recorder = recorder.borrow_mut();
+            match recorder.iter_mut().find(|s| s.name == stats.name) {
+                Some(existing) => existing.merge(&stats),
+                None => recorder.push(stats),
+            }
+        });
+    }
+}
+
+/// Cache statistics of one or more memos sharing a name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoStats {
+    pub name: &'static str,
+    pub instances: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: u64,
+}
+
+impl MemoStats {
+    fn merge(&mut self, other: &MemoStats) {
+        self.instances += other.instances;
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.entries += other.entries;
+    }
+
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            return 0.0;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let rate = self.hits as f64 / lookups as f64;
+        rate
+    }
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} hits, {} misses ({:.1}% hit rate), {} entries",
+            self.name,
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0,
+            self.entries
+        )?;
+
+        if self.instances > 1 {
+            write!(f, " in {} memos", self.instances)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Clears previous statistics and starts collecting them from memos dropped on the current thread.
+pub fn start_recording() {
+    RECORDER.with(|recorder| recorder.borrow_mut().clear());
+    ENABLED.with(|enabled| enabled.set(true));
+}
+
+/// Stops collecting and returns the statistics of all memos dropped since [`start_recording`].
+pub fn finish_recording() -> Vec<MemoStats> {
+    ENABLED.with(|enabled| enabled.set(false));
+    RECORDER.with(|recorder| recorder.take())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{finish_recording, start_recording, Memo};
+
+    fn paths(memo: &mut Memo<(u32, u32), u64>, x: u32, y: u32) -> u64 {
+        memo.get_or_compute((x, y), |memo, &(x, y)| match (x, y) {
+            (0, _) | (_, 0) => 1,
+            _ => paths(memo, x - 1, y) + paths(memo, x, y - 1),
+        })
+    }
+
+    #[test]
+    fn recursion() {
+        let mut memo = Memo::new();
+        assert_eq!(paths(&mut memo, 16, 16), 601_080_390);
+
+        let stats = memo.stats();
+        // Every point of the 17x17 grid except the origin, which no path passes.
+        assert_eq!(stats.misses, 17 * 17 - 1);
+        assert_eq!(stats.entries, 17 * 17 - 1);
+        assert!(stats.hits > 0);
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let words = String::from("a b a c a");
+        let mut memo: Memo<&str, usize> = Memo::new();
+
+        for word in words.split(' ') {
+            memo.get_or_compute(word, |_, word| word.len());
+        }
+
+        assert_eq!(memo.stats().hits, 2);
+        assert_eq!(memo.get(&"c"), Some(&1));
+    }
+
+    #[test]
+    fn dense() {
+        let mut memo = Memo::dense(10);
+        assert_eq!(memo.get_or_compute(3, |_, &k| k * 2), 6);
+        assert_eq!(memo.get_or_compute(3, |_, _| unreachable!()), 6);
+        assert_eq!(memo.stats().entries, 1);
+    }
+
+    #[test]
+    fn records_stats_by_name() {
+        start_recording();
+
+        for _ in 0..3 {
+            let mut memo = Memo::new().named("squares");
+            memo.get_or_compute(2, |_, &k: &u32| k * k);
+            memo.get_or_compute(2, |_, &k: &u32| k * k);
+        }
+
+        let mut dense = Memo::dense(4).named("dense");
+        dense.get_or_compute(1, |_, &k| k);
+        let reported = dense.stats();
+        drop(dense);
+
+        let stats = finish_recording();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].instances, 3);
+        assert_eq!(stats[0].hits, 3);
+        assert_eq!(stats[0].misses, 3);
+        assert_eq!(stats[0].entries, 3);
+        assert_eq!(stats[1], reported);
+    }
+}
@@ -0,0 +1,307 @@
+/// Helpers for the formats that keep coming back in puzzle inputs.
+///
+/// Errors carry the 1-based line and column they occurred at. Helpers that work on a single line
+/// report line `1`, the [`lines`] adapter and [`Section`] move errors to the line they were found on.
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::grid::{Grid, ParseGridError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A number that does not fit the requested type, e.g. a negative number for `u32`.
+    InvalidNumber(String),
+    /// The line does not start with the expected label.
+    MissingLabel(String),
+    /// A line or section that was required is missing.
+    Missing(&'static str),
+    Grid(ParseGridError),
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, kind: ParseErrorKind) -> Self {
+        Self { line, column, kind }
+    }
+
+    /// Moves an error found in a part of the input that starts at `line`.
+    #[must_use]
+    pub fn offset(mut self, line: usize) -> Self {
+        self.line += line - 1;
+        self
+    }
+}
+
+impl Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+
+        match &self.kind {
+            ParseErrorKind::InvalidNumber(text) => write!(f, "invalid number {text:?}"),
+            ParseErrorKind::MissingLabel(label) => write!(f, "expected {label:?}"),
+            ParseErrorKind::Missing(what) => write!(f, "missing {what}"),
+            ParseErrorKind::Grid(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<ParseGridError> for ParseError {
+    fn from(e: ParseGridError) -> Self {
+        let (line, column) = match e {
+            ParseGridError::Empty => (1, 1),
+            ParseGridError::RaggedLine { line, expected, .. } => (line, expected + 1),
+            ParseGridError::InvalidCell { line, column, .. } => (line, column),
+        };
+
+        Self::new(line, column, ParseErrorKind::Grid(e))
+    }
+}
+
+/// The 1-based column of the byte at `offset` in `line`.
+fn column(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+/// All integers in `line`, in order. A `-` directly in front of a number makes it negative.
+///
+/// ```
+/// # use advent_of_code::parse::ints;
+/// assert_eq!(ints::<i32>("Time: 7 -15 30"), Ok(vec![7, -15, 30]));
+/// assert_eq!(ints::<u8>("x=3, y=4"), Ok(vec![3, 4]));
+/// assert!(ints::<u8>("300").is_err());
+/// ```
+pub fn ints<T: FromStr>(line: &str) -> Result<Vec<T>, ParseError> {
+    let bytes = line.as_bytes();
+    let mut numbers = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let is_negative = bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+
+        if !is_negative && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        let text = &line[start..i];
+        let number = text.parse().map_err(|_| {
+            ParseError::new(
+                1,
+                column(line, start),
+                ParseErrorKind::InvalidNumber(text.to_string()),
+            )
+        })?;
+
+        numbers.push(number);
+    }
+
+    Ok(numbers)
+}
+
+/// The rest of `line` after `label`, without surrounding whitespace.
+///
+/// ```
+/// # use advent_of_code::parse::labeled;
+/// assert_eq!(labeled("Time:      7  15   30", "Time:"), Ok("7  15   30"));
+/// assert!(labeled("Distance: 9", "Time:").is_err());
+/// ```
+pub fn labeled<'a>(line: &'a str, label: &str) -> Result<&'a str, ParseError> {
+    let trimmed = line.trim_start();
+
+    trimmed.strip_prefix(label).map(str::trim).ok_or_else(|| {
+        ParseError::new(
+            1,
+            column(line, line.len() - trimmed.len()),
+            ParseErrorKind::MissingLabel(label.to_string()),
+        )
+    })
+}
+
+/// Parses every non-empty line with `f`. Errors are moved to the line they occurred on.
+pub fn lines<T>(
+    input: &str,
+    mut f: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| f(line).map_err(|e| e.offset(i + 1)))
+        .collect()
+}
+
+/// Parses a grid of cells, see [`Grid`].
+pub fn grid<T: TryFrom<char>>(input: &str) -> Result<Grid<T>, ParseError> {
+    Ok(input.parse()?)
+}
+
+/// A block of lines, separated from the others by blank lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+    pub text: &'a str,
+    /// The 1-based line the section starts at in the whole input.
+    pub line: usize,
+}
+
+impl<'a> Section<'a> {
+    /// The first line of the section, e.g. a header like `seed-to-soil map:`.
+    pub fn header(&self) -> &'a str {
+        self.text.lines().next().unwrap_or_default()
+    }
+
+    /// The lines after the first one.
+    pub fn body(&self) -> Section<'a> {
+        let text = match self.text.split_once('\n') {
+            Some((_, body)) => body,
+            None => "",
+        };
+
+        Section {
+            text,
+            line: self.line + 1,
+        }
+    }
+
+    /// Like [`lines`], with errors reported at their line in the whole input.
+    pub fn lines<T>(
+        &self,
+        f: impl FnMut(&str) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        lines(self.text, f).map_err(|e| e.offset(self.line))
+    }
+
+    /// Like [`grid`], with errors reported at their line in the whole input.
+    pub fn grid<T: TryFrom<char>>(&self) -> Result<Grid<T>, ParseError> {
+        grid(self.text).map_err(|e| e.offset(self.line))
+    }
+
+    /// Moves an error found in [`Section::text`] to its line in the whole input.
+    pub fn error(&self, e: ParseError) -> ParseError {
+        e.offset(self.line)
+    }
+}
+
+/// Splits `input` on blank lines.
+///
+/// ```
+/// # use advent_of_code::parse::sections;
+/// let sections = sections("seeds: 1 2\n\nmap:\n1 2 3\n");
+/// assert_eq!(sections.len(), 2);
+/// assert_eq!(sections[1].header(), "map:");
+/// assert_eq!(sections[1].line, 3);
+/// ```
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut sections = vec![];
+    let mut start: Option<(usize, usize)> = None;
+    let mut offset = 0;
+
+    for (i, line) in input.split_inclusive('\n').enumerate() {
+        let is_blank = line.trim().is_empty();
+
+        match (start, is_blank) {
+            (None, false) => start = Some((offset, i + 1)),
+            (Some((from, line)), true) => {
+                sections.push(Section {
+                    text: input[from..offset].trim_end(),
+                    line,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+
+        offset += line.len();
+    }
+
+    if let Some((from, line)) = start {
+        sections.push(Section {
+            text: input[from..].trim_end(),
+            line,
+        });
+    }
+
+    sections
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{grid, ints, labeled, lines, sections, ParseError, ParseErrorKind};
+    use crate::grid::Grid;
+
+    #[test]
+    fn integers() {
+        assert_eq!(ints::<i64>("-3 4-5 x-y -"), Ok(vec![-3, 4, -5]));
+        assert_eq!(ints::<u32>(""), Ok(vec![]));
+        assert_eq!(
+            ints::<u32>("1 -2"),
+            Err(ParseError::new(
+                1,
+                3,
+                ParseErrorKind::InvalidNumber("-2".into())
+            ))
+        );
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!(labeled("  Card 1: 41 48", "Card"), Ok("1: 41 48"));
+        assert_eq!(
+            labeled("  Game 1", "Card").unwrap_err().column,
+            3,
+            "column of the first non-blank character"
+        );
+    }
+
+    #[test]
+    fn line_numbers() {
+        let input = "1 2\n\n3 x\n4 99999999999\n";
+        let e = lines(input, ints::<u32>).unwrap_err();
+
+        assert_eq!((e.line, e.column), (4, 3));
+        assert_eq!(
+            e.to_string(),
+            "line 4, column 3: invalid number \"99999999999\""
+        );
+    }
+
+    #[test]
+    fn split_sections() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\nlast:\n-1 x\n";
+        let sections = sections(input);
+
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0].text, "seeds: 79 14");
+        assert_eq!(sections[1].header(), "seed-to-soil map:");
+        assert_eq!(
+            sections[1].body().lines(ints::<u64>),
+            Ok(vec![vec![50, 98, 2], vec![52, 50, 48]])
+        );
+
+        let e = sections[2].body().lines(ints::<u64>).unwrap_err();
+        assert_eq!((e.line, e.column), (9, 1));
+    }
+
+    #[test]
+    fn grids() {
+        let parsed: Grid<char> = grid("ab\ncd\n").unwrap();
+        assert_eq!(parsed.height(), 2);
+
+        let e = sections("header\n\nab\nc\n")[1].grid::<char>().unwrap_err();
+        assert_eq!((e.line, e.column), (4, 3));
+    }
+}
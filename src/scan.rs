@@ -0,0 +1,407 @@
+/// Parsing lines against format-string-like patterns, see [`scan!`](crate::scan!).
+use std::error::Error;
+use std::fmt::Display;
+
+/// Matches `line` against a pattern and parses every placeholder into the next type of a tuple.
+///
+/// - `{}` captures everything up to the following literal text of the pattern, or to the end of the line.
+///   Captures are trimmed before they are parsed.
+/// - `{*SEP}` captures a list separated by `SEP`, e.g. `{*, }`, and parses into a `Vec`.
+/// - `{{` and `}}` match literal braces.
+///
+/// Returns a `Result` with the tuple, or a [`ScanError`] with the column where matching failed.
+///
+/// ```
+/// use advent_of_code::scan;
+///
+/// let (node, left, right) = scan!("AAA = (BBB, CCC)", "{} = ({}, {})" => (&str, &str, &str)).unwrap();
+/// assert_eq!((node, left, right), ("AAA", "BBB", "CCC"));
+///
+/// let (id, sets) = scan!("Game 12: 3 blue; 1 red", "Game {}: {*; }" => (u32, Vec<&str>)).unwrap();
+/// assert_eq!((id, sets), (12, vec!["3 blue", "1 red"]));
+///
+/// let e = scan!("Game x: 3 blue", "Game {}: {}" => (u32, &str)).unwrap_err();
+/// assert_eq!(e.column, 6);
+/// ```
+#[macro_export]
+macro_rules! scan {
+    ($line:expr, $pattern:literal => ($($t:ty),+ $(,)?)) => {{
+        let line: &str = $line;
+        let count = [$(stringify!($t)),+].len();
+
+        $crate::scan::captures(line, $pattern, count).and_then(|captures| {
+            let mut captures = captures.into_iter();
+            Ok(($(
+                $crate::scan::parse_capture::<$t>(line, captures.next().expect("count was checked"))?,
+            )+))
+        })
+    }};
+}
+
+/// Types that a placeholder of [`scan!`](crate::scan!) can be parsed into.
+///
+/// Implemented for numbers, `char`, `bool`, strings, `Vec`s of them (for `{*SEP}` placeholders)
+/// and pairs and triples, which are split on whitespace, e.g. `(u32, &str)` for `3 blue`.
+/// Other types can use [`parse_with_from_str`] in their implementation.
+pub trait Scan<'a>: Sized {
+    /// Parses a trimmed capture. On failure, returns the part of `text` that did not match.
+    fn scan(text: &'a str) -> Result<Self, Mismatch<'a>>;
+
+    /// Parses a list capture. Only implemented by list types.
+    fn scan_list(text: &'a str, separator: &str) -> Result<Self, Mismatch<'a>> {
+        let _ = separator;
+        Err(Mismatch::new(text, "a single value, not a list"))
+    }
+}
+
+/// The part of a capture that could not be parsed, and what was expected instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch<'a> {
+    pub text: &'a str,
+    pub expected: &'static str,
+}
+
+impl<'a> Mismatch<'a> {
+    pub fn new(text: &'a str, expected: &'static str) -> Self {
+        Self { text, expected }
+    }
+}
+
+/// Implements [`Scan::scan`] with the type's `FromStr` implementation.
+pub fn parse_with_from_str<T: std::str::FromStr>(text: &str) -> Result<T, Mismatch<'_>> {
+    text.parse()
+        .map_err(|_| Mismatch::new(text, std::any::type_name::<T>()))
+}
+
+macro_rules! impl_scan_from_str {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> Scan<'a> for $t {
+                fn scan(text: &'a str) -> Result<Self, Mismatch<'a>> {
+                    parse_with_from_str(text)
+                }
+            }
+        )*
+    };
+}
+
+impl_scan_from_str!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool, char, String
+);
+
+impl<'a> Scan<'a> for &'a str {
+    fn scan(text: &'a str) -> Result<Self, Mismatch<'a>> {
+        Ok(text)
+    }
+}
+
+impl<'a, T: Scan<'a>> Scan<'a> for Vec<T> {
+    fn scan(text: &'a str) -> Result<Self, Mismatch<'a>> {
+        Err(Mismatch::new(text, "a list placeholder like {*, }"))
+    }
+
+    fn scan_list(text: &'a str, separator: &str) -> Result<Self, Mismatch<'a>> {
+        if text.is_empty() {
+            return Ok(vec![]);
+        }
+
+        text.split(separator)
+            .map(|item| T::scan(item.trim()))
+            .collect()
+    }
+}
+
+impl<'a, A: Scan<'a>, B: Scan<'a>> Scan<'a> for (A, B) {
+    fn scan(text: &'a str) -> Result<Self, Mismatch<'a>> {
+        match text.split_whitespace().collect::<Vec<_>>()[..] {
+            [a, b] => Ok((A::scan(a)?, B::scan(b)?)),
+            _ => Err(Mismatch::new(text, "2 words")),
+        }
+    }
+}
+
+impl<'a, A: Scan<'a>, B: Scan<'a>, C: Scan<'a>> Scan<'a> for (A, B, C) {
+    fn scan(text: &'a str) -> Result<Self, Mismatch<'a>> {
+        match text.split_whitespace().collect::<Vec<_>>()[..] {
+            [a, b, c] => Ok((A::scan(a)?, B::scan(b)?, C::scan(c)?)),
+            _ => Err(Mismatch::new(text, "3 words")),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanError {
+    /// The 1-based column in the line where matching failed.
+    pub column: usize,
+    pub kind: ScanErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanErrorKind {
+    /// The line does not contain the literal text of the pattern.
+    ExpectedLiteral(String),
+    /// The line continues after the end of the pattern.
+    TrailingText(String),
+    /// A capture could not be parsed into its type.
+    InvalidValue {
+        text: String,
+        expected: &'static str,
+    },
+    /// The pattern itself is malformed, or its placeholders don't match the types.
+    InvalidPattern(String),
+}
+
+impl Error for ScanError {}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "column {}: ", self.column)?;
+
+        match &self.kind {
+            ScanErrorKind::ExpectedLiteral(literal) => write!(f, "expected {literal:?}"),
+            ScanErrorKind::TrailingText(text) => write!(f, "unexpected {text:?} after the pattern"),
+            ScanErrorKind::InvalidValue { text, expected } => {
+                write!(f, "expected {expected}, found {text:?}")
+            }
+            ScanErrorKind::InvalidPattern(message) => write!(f, "invalid pattern: {message}"),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Literal(String),
+    Capture { separator: Option<String> },
+}
+
+fn tokenize(pattern: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut literal = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => inner.push(c),
+                        None => return Err("unclosed '{'".into()),
+                    }
+                }
+
+                let separator = match inner.strip_prefix('*') {
+                    Some("") => return Err("list placeholder without separator".into()),
+                    Some(separator) => Some(separator.to_string()),
+                    None if inner.is_empty() => None,
+                    None => return Err(format!("unknown placeholder {{{inner}}}")),
+                };
+
+                if matches!(tokens.last(), Some(Token::Capture { .. })) && literal.is_empty() {
+                    return Err("placeholders need literal text between them".into());
+                }
+
+                if !literal.is_empty() {
+                    tokens.push(Token::Literal(std::mem::take(&mut literal)));
+                }
+                tokens.push(Token::Capture { separator });
+            }
+            '}' => return Err("unmatched '}'".into()),
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(literal));
+    }
+
+    Ok(tokens)
+}
+
+/// A matched placeholder of [`scan!`](crate::scan!).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capture<'a> {
+    text: &'a str,
+    separator: Option<String>,
+}
+
+/// Splits `line` into the captures of `pattern`. Used by [`scan!`](crate::scan!).
+pub fn captures<'a>(
+    line: &'a str,
+    pattern: &str,
+    expected_count: usize,
+) -> Result<Vec<Capture<'a>>, ScanError> {
+    let invalid_pattern = |message: String| ScanError {
+        column: 1,
+        kind: ScanErrorKind::InvalidPattern(message),
+    };
+
+    let tokens = tokenize(pattern).map_err(invalid_pattern)?;
+
+    let count = tokens
+        .iter()
+        .filter(|token| matches!(token, Token::Capture { .. }))
+        .count();
+    if count != expected_count {
+        return Err(invalid_pattern(format!(
+            "{count} placeholders for {expected_count} types"
+        )));
+    }
+
+    let mut captures = vec![];
+    let mut rest = line;
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Literal(literal) => {
+                rest = rest
+                    .strip_prefix(literal.as_str())
+                    .ok_or_else(|| ScanError {
+                        column: column(line, rest),
+                        kind: ScanErrorKind::ExpectedLiteral(literal),
+                    })?;
+            }
+            Token::Capture { separator } => {
+                let end = match tokens.peek() {
+                    Some(Token::Literal(literal)) => {
+                        rest.find(literal.as_str()).ok_or_else(|| ScanError {
+                            column: column(line, rest),
+                            kind: ScanErrorKind::ExpectedLiteral(literal.clone()),
+                        })?
+                    }
+                    _ => rest.len(),
+                };
+
+                let (text, tail) = rest.split_at(end);
+                captures.push(Capture {
+                    text: text.trim(),
+                    separator,
+                });
+                rest = tail;
+            }
+        }
+    }
+
+    if !rest.trim_end().is_empty() {
+        return Err(ScanError {
+            column: column(line, rest),
+            kind: ScanErrorKind::TrailingText(rest.to_string()),
+        });
+    }
+
+    Ok(captures)
+}
+
+/// Parses a capture into its type. Used by [`scan!`](crate::scan!).
+pub fn parse_capture<'a, T: Scan<'a>>(line: &'a str, capture: Capture<'a>) -> Result<T, ScanError> {
+    let result = match &capture.separator {
+        Some(separator) => T::scan_list(capture.text, separator),
+        None => T::scan(capture.text),
+    };
+
+    result.map_err(|mismatch| ScanError {
+        column: column(line, mismatch.text),
+        kind: ScanErrorKind::InvalidValue {
+            text: mismatch.text.to_string(),
+            expected: mismatch.expected,
+        },
+    })
+}
+
+/// The 1-based column at which `part`, a sub-slice of `line`, starts.
+fn column(line: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize)
+        .saturating_sub(line.as_ptr() as usize)
+        .min(line.len());
+
+    line[..offset].chars().count() + 1
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{ScanError, ScanErrorKind};
+
+    #[test]
+    fn tuples() {
+        let result = crate::scan!("rn=1", "{}={}" => (&str, u8));
+        assert_eq!(result, Ok(("rn", 1)));
+
+        let result = crate::scan!("x: {5}", "{}: {{{}}}" => (char, i32));
+        assert_eq!(result, Ok(('x', 5)));
+
+        let result = crate::scan!("42", "{}" => (u64,));
+        assert_eq!(result, Ok((42,)));
+    }
+
+    #[test]
+    fn lists() {
+        let result = crate::scan!(
+            "Game 100: 3 blue, 4 red; 2 green",
+            "Game {}: {*; }" => (u32, Vec<String>)
+        );
+        assert_eq!(
+            result,
+            Ok((
+                100,
+                vec!["3 blue, 4 red".to_string(), "2 green".to_string()]
+            ))
+        );
+
+        let result = crate::scan!("3 blue, 4 red", "{*,}" => (Vec<(u32, &str)>,));
+        assert_eq!(result, Ok((vec![(3, "blue"), (4, "red")],)));
+    }
+
+    #[test]
+    fn errors() {
+        let result = crate::scan!("AAA - (BBB, CCC)", "{} = ({}, {})" => (&str, &str, &str));
+        assert_eq!(
+            result,
+            Err(ScanError {
+                column: 1,
+                kind: ScanErrorKind::ExpectedLiteral(" = (".into())
+            })
+        );
+
+        let e = crate::scan!("1 red, x green", "{*, }" => (Vec<(u8, &str)>,)).unwrap_err();
+        assert_eq!(e.column, 8);
+        assert_eq!(e.to_string(), "column 8: expected u8, found \"x\"");
+
+        let e = crate::scan!("1=2", "{}={}" => (u8,)).unwrap_err();
+        assert!(matches!(e.kind, ScanErrorKind::InvalidPattern(_)));
+
+        let e = crate::scan!("a=2", "{}={}" => (u8, u8)).unwrap_err();
+        assert_eq!(
+            e.kind,
+            ScanErrorKind::InvalidValue {
+                text: "a".into(),
+                expected: "u8"
+            }
+        );
+
+        let e = crate::scan!("cm-x", "{}-x!" => (&str,)).unwrap_err();
+        assert_eq!(e.column, 1);
+
+        let e = crate::scan!("cm-x", "{}-" => (&str,)).unwrap_err();
+        assert_eq!(
+            e,
+            ScanError {
+                column: 4,
+                kind: ScanErrorKind::TrailingText("x".into())
+            }
+        );
+    }
+}
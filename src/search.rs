@@ -0,0 +1,385 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The cheapest way to a goal found by a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<S, C> {
+    /// Total cost of the path, the amount of steps for [`bfs`].
+    pub cost: C,
+    /// All states from the start state to the goal state, both included.
+    pub path: Vec<S>,
+}
+
+impl<S, C> Found<S, C> {
+    /// The goal state that was reached.
+    pub fn goal(&self) -> &S {
+        self.path
+            .last()
+            .expect("a path contains at least the start state")
+    }
+}
+
+const NO_PARENT: usize = usize::MAX;
+
+/// How [`Visited`] finds the index of a state it has seen before.
+trait Interner<S> {
+    /// The index of `state` if it was seen before.
+    /// Otherwise remembers `state` at index `next` and hands it back.
+    fn intern(&mut self, state: S, next: usize) -> Result<usize, S>;
+}
+
+impl<S: Clone + Eq + Hash> Interner<S> for HashMap<S, usize> {
+    fn intern(&mut self, state: S, next: usize) -> Result<usize, S> {
+        match self.entry(state) {
+            Entry::Occupied(entry) => Ok(*entry.get()),
+            Entry::Vacant(entry) => {
+                let state = entry.key().clone();
+                entry.insert(next);
+                Err(state)
+            }
+        }
+    }
+}
+
+/// Interns states that map to a unique key in `0..size` without hashing them.
+struct Dense<F> {
+    indices: Vec<usize>,
+    key: F,
+}
+
+impl<S, F: Fn(&S) -> usize> Interner<S> for Dense<F> {
+    fn intern(&mut self, state: S, next: usize) -> Result<usize, S> {
+        let slot = &mut self.indices[(self.key)(&state)];
+
+        if *slot == NO_PARENT {
+            *slot = next;
+            Err(state)
+        } else {
+            Ok(*slot)
+        }
+    }
+}
+
+/// Interns states so that parents can be tracked without cloning states again.
+struct Visited<S, X = HashMap<S, usize>> {
+    index: X,
+    states: Vec<S>,
+    parents: Vec<usize>,
+}
+
+impl<S: Clone + Eq + Hash> Visited<S> {
+    fn new() -> Self {
+        Self::with_interner(HashMap::new())
+    }
+}
+
+impl<S: Clone, X: Interner<S>> Visited<S, X> {
+    fn with_interner(index: X) -> Self {
+        Self {
+            index,
+            states: vec![],
+            parents: vec![],
+        }
+    }
+
+    /// Returns the index of `state`, and whether it was seen for the first time.
+    fn insert(&mut self, state: S, parent: usize) -> (usize, bool) {
+        match self.index.intern(state, self.states.len()) {
+            Ok(index) => (index, false),
+            Err(state) => {
+                self.states.push(state);
+                self.parents.push(parent);
+                (self.states.len() - 1, true)
+            }
+        }
+    }
+
+    fn path(&self, mut index: usize) -> Vec<S> {
+        let mut path = vec![];
+
+        while index != NO_PARENT {
+            path.push(self.states[index].clone());
+            index = self.parents[index];
+        }
+
+        path.reverse();
+        path
+    }
+}
+
+/// Breadth-first search for the goal with the fewest steps from any of the `starts`.
+///
+/// ```
+/// # use advent_of_code::search::bfs;
+/// let found = bfs([1], |&n| [n + 1, n * 2], |&n| n == 10).unwrap();
+/// assert_eq!(found.cost, 4);
+/// assert_eq!(found.path, vec![1, 2, 4, 5, 10]);
+/// ```
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = Visited::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        let (index, is_new) = visited.insert(start, NO_PARENT);
+        if is_new {
+            queue.push_back((index, 0));
+        }
+    }
+
+    while let Some((index, steps)) = queue.pop_front() {
+        if is_goal(&visited.states[index]) {
+            return Some(Found {
+                cost: steps,
+                path: visited.path(index),
+            });
+        }
+
+        let state = visited.states[index].clone();
+        for next in successors(&state) {
+            let (next_index, is_new) = visited.insert(next, index);
+            if is_new {
+                queue.push_back((next_index, steps + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Breadth-first search that visits every state reachable from the `starts`.
+/// Returns the fewest amount of steps to reach each of them.
+pub fn bfs_reach<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if !distances.contains_key(&start) {
+            distances.insert(start.clone(), 0);
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((state, steps)) = queue.pop_front() {
+        for next in successors(&state) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), steps + 1);
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    distances
+}
+
+/// Dijkstra's algorithm: finds the cheapest path from any of the `starts` to a goal.
+/// `successors` yields the next states together with the (non-negative) cost of moving there.
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(starts, successors, |_| C::default(), is_goal)
+}
+
+/// Like [`dijkstra`], for states that map to a unique `key` in `0..size`.
+/// Keeps track of the visited states in an array instead of hashing them.
+///
+/// ```
+/// # use advent_of_code::search::dijkstra_dense;
+/// let successors = |&n: &usize| [(n + 1, 1), (n * 3, 1)].into_iter().filter(|&(n, _)| n < 100);
+/// let found = dijkstra_dense(100, |&n| n, [1], successors, |&n| n == 30).unwrap();
+/// assert_eq!(found.cost, 4);
+/// assert_eq!(found.path, vec![1, 3, 9, 10, 30]);
+/// ```
+pub fn dijkstra_dense<S, C, I>(
+    size: usize,
+    key: impl Fn(&S) -> usize,
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let visited = Visited::with_interner(Dense {
+        indices: vec![NO_PARENT; size],
+        key,
+    });
+
+    search(visited, starts, successors, |_| C::default(), is_goal)
+}
+
+/// A* search: like [`dijkstra`], but explores states in order of their cost plus `heuristic`.
+/// States are never revisited, so the heuristic must be consistent: it never drops by more than the cost of a move,
+/// `heuristic(a) <= cost(a, b) + heuristic(b)`, and is zero at goals. Never overestimating the remaining cost is not
+/// enough, with an inconsistent heuristic the result might not be the cheapest.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    search(Visited::new(), starts, successors, heuristic, is_goal)
+}
+
+/// The A* search shared by [`astar`], [`dijkstra`] and [`dijkstra_dense`].
+fn search<S, C, I, X>(
+    mut visited: Visited<S, X>,
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+    X: Interner<S>,
+{
+    let mut best: Vec<C> = vec![];
+    let mut done: Vec<bool> = vec![];
+    let mut queue = BinaryHeap::new();
+
+    for start in starts {
+        let (index, is_new) = visited.insert(start, NO_PARENT);
+        if is_new {
+            best.push(C::default());
+            done.push(false);
+            let estimate = heuristic(&visited.states[index]);
+            queue.push((Reverse(estimate), Reverse(C::default()), index));
+        }
+    }
+
+    while let Some((_, Reverse(cost), index)) = queue.pop() {
+        if done[index] || cost > best[index] {
+            continue;
+        }
+        done[index] = true;
+
+        if is_goal(&visited.states[index]) {
+            return Some(Found {
+                cost,
+                path: visited.path(index),
+            });
+        }
+
+        let state = visited.states[index].clone();
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            let (next_index, is_new) = visited.insert(next, index);
+
+            if is_new {
+                best.push(next_cost);
+                done.push(false);
+            } else if done[next_index] || next_cost >= best[next_index] {
+                continue;
+            } else {
+                best[next_index] = next_cost;
+                visited.parents[next_index] = index;
+            }
+
+            let estimate = next_cost + heuristic(&visited.states[next_index]);
+            queue.push((Reverse(estimate), Reverse(next_cost), next_index));
+        }
+    }
+
+    None
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{astar, bfs, bfs_reach, dijkstra, dijkstra_dense};
+
+    /// Weighted graph: 0 -> 1 (1), 0 -> 2 (4), 1 -> 2 (2), 2 -> 3 (1), 1 -> 3 (6)
+    fn edges(n: &u32) -> Vec<(u32, u32)> {
+        match n {
+            0 => vec![(1, 1), (2, 4)],
+            1 => vec![(2, 2), (3, 6)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn bfs_fewest_steps() {
+        let found = bfs([0], |n| edges(n).into_iter().map(|(n, _)| n), |&n| n == 3).unwrap();
+        assert_eq!(found.cost, 2);
+        assert_eq!(found.path.first(), Some(&0));
+        assert_eq!(found.goal(), &3);
+
+        assert!(bfs([0], |n| edges(n).into_iter().map(|(n, _)| n), |&n| n == 7).is_none());
+    }
+
+    #[test]
+    fn bfs_reaches_all() {
+        let reach = bfs_reach([0], |n| edges(n).into_iter().map(|(n, _)| n));
+        assert_eq!(reach.len(), 4);
+        assert_eq!(reach[&3], 2);
+    }
+
+    #[test]
+    fn dijkstra_cheapest_path() {
+        let found = dijkstra([0], edges, |&n| n == 3).unwrap();
+        assert_eq!(found.cost, 4);
+        assert_eq!(found.path, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn dense_states() {
+        let found = dijkstra_dense(4, |&n| n as usize, [0], edges, |&n| n == 3).unwrap();
+        assert_eq!(found, dijkstra([0], edges, |&n| n == 3).unwrap());
+
+        assert!(dijkstra_dense(4, |&n| n as usize, [3], edges, |&n| n == 0).is_none());
+    }
+
+    #[test]
+    fn multiple_starts() {
+        let found = dijkstra([0, 2], edges, |&n| n == 3).unwrap();
+        assert_eq!(found.cost, 1);
+        assert_eq!(found.path, vec![2, 3]);
+    }
+
+    #[test]
+    fn astar_on_a_line() {
+        let target = 20_i32;
+        let found = astar(
+            [0_i32],
+            |&n| [(n - 1, 1), (n + 1, 1)],
+            |&n| (target - n).abs(),
+            |&n| n == target,
+        )
+        .unwrap();
+        assert_eq!(found.cost, 20);
+        assert_eq!(found.path.len(), 21);
+    }
+}
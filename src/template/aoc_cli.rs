@@ -60,18 +60,14 @@ pub fn download(day: Day) -> Result<Output, AocCommandError> {
         &[
             "--overwrite".into(),
             "--input-file".into(),
-            input_path.to_string(),
+            input_path,
             "--puzzle-file".into(),
-            puzzle_path.to_string(),
+            puzzle_path,
         ],
         day,
     );
 
-    let output = call_aoc_cli(&args)?;
-    println!("---");
-    println!("🎄 Successfully wrote input to \"{}\".", &input_path);
-    println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
-    Ok(output)
+    call_aoc_cli(&args)
 }
 
 pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandError> {
@@ -82,11 +78,11 @@ pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandErro
     call_aoc_cli(&args)
 }
 
-fn get_input_path(day: Day) -> String {
+pub fn get_input_path(day: Day) -> String {
     format!("data/inputs/{day}.txt")
 }
 
-fn get_puzzle_path(day: Day) -> String {
+pub fn get_puzzle_path(day: Day) -> String {
     format!("data/puzzles/{day}.md")
 }
 
@@ -1,59 +1,93 @@
-use std::io;
-
 use crate::template::{
+    commands::CommandError,
     readme_benchmarks::{self, Timings},
-    ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
+    ANSI_BOLD, ANSI_RESET,
 };
 use crate::{all_days, Day};
 
-pub fn handle(is_release: bool, is_timed: bool) {
-    let mut timings: Vec<Timings> = vec![];
+/// Result of running a single day as part of [`run`].
+#[derive(Clone)]
+pub struct DayReport {
+    pub day: Day,
+    /// Lines the solution printed to stdout. Empty if the day has not been solved yet.
+    pub output: Vec<String>,
+    pub timings: Option<Timings>,
+}
 
-    all_days().for_each(|day| {
-        if day > 1 {
-            println!();
-        }
+impl DayReport {
+    pub fn is_solved(&self) -> bool {
+        !self.output.is_empty()
+    }
+}
+
+/// Result of [`run`].
+pub struct AllReport {
+    pub days: Vec<DayReport>,
+    /// Sum of all benchmarked part timings. Only set when timing.
+    pub total_millis: Option<f64>,
+    /// Outcome of writing the benchmark table to the readme. Only set when timing a release build.
+    pub readme_update: Option<Result<(), readme_benchmarks::Error>>,
+}
 
-        println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-        println!("------");
+/// Runs the solutions of all days sequentially.
+/// If `echo` is set, a header for every day and the solution output are printed while running.
+pub fn run(is_release: bool, is_timed: bool, echo: bool) -> Result<AllReport, CommandError> {
+    let mut days = vec![];
 
-        let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+    for day in all_days() {
+        if echo {
+            if day > 1 {
+                println!();
+            }
+
+            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+            println!("------");
+        }
+
+        let output = child_commands::run_solution(day, is_timed, is_release, echo)?;
 
         if output.is_empty() {
-            println!("Not solved.");
+            if echo {
+                println!("Not solved.");
+            }
+
+            days.push(DayReport {
+                day,
+                output,
+                timings: None,
+            });
         } else {
-            let val = child_commands::parse_exec_time(&output, day);
-            timings.push(val);
+            let timings = child_commands::parse_exec_time(&output, day);
+            days.push(DayReport {
+                day,
+                output,
+                timings: Some(timings),
+            });
         }
-    });
+    }
+
+    let mut report = AllReport {
+        days,
+        total_millis: None,
+        readme_update: None,
+    };
 
     if is_timed {
+        let timings: Vec<Timings> = report
+            .days
+            .iter()
+            .filter_map(|d| d.timings.clone())
+            .collect();
         let total_millis = timings.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64;
 
-        println!("\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}");
+        report.total_millis = Some(total_millis);
 
         if is_release {
-            match readme_benchmarks::update(timings, total_millis) {
-                Ok(()) => println!("Successfully updated README with benchmarks."),
-                Err(_) => {
-                    eprintln!("Failed to update readme with benchmarks.");
-                }
-            }
+            report.readme_update = Some(readme_benchmarks::update(timings, total_millis));
         }
     }
-}
-
-#[derive(Debug)]
-pub enum Error {
-    BrokenPipe,
-    Parser(String),
-    IO(io::Error),
-}
 
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Self {
-        Error::IO(e)
-    }
+    Ok(report)
 }
 
 #[must_use]
@@ -64,17 +98,23 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 mod child_commands {
-    use super::{get_path_for_bin, Error};
+    use super::get_path_for_bin;
+    use crate::template::commands::CommandError;
     use crate::Day;
     use std::{
-        io::{BufRead, BufReader},
+        io::{self, BufRead, BufReader},
         path::Path,
         process::{Command, Stdio},
         thread,
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Run the solution bin for a given day. If `echo` is set, its output is forwarded to stdout / stderr.
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        echo: bool,
+    ) -> Result<Vec<String>, CommandError> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
             return Ok(vec![]);
@@ -102,24 +142,29 @@ mod child_commands {
             .stderr(Stdio::piped())
             .spawn()?;
 
-        let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
-        let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
+        let broken_pipe = || CommandError::Process(io::ErrorKind::BrokenPipe.into());
+        let stdout = BufReader::new(cmd.stdout.take().ok_or_else(broken_pipe)?);
+        let stderr = BufReader::new(cmd.stderr.take().ok_or_else(broken_pipe)?);
 
         let mut output = vec![];
 
         let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
+            stderr.lines().map_while(Result::ok).for_each(|line| {
+                if echo {
+                    eprintln!("{line}");
+                }
             });
         });
 
         for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
+            let line = line?;
+            if echo {
+                println!("{line}");
+            }
             output.push(line);
         }
 
-        thread.join().unwrap();
+        thread.join().map_err(|_| broken_pipe())?;
         cmd.wait()?;
 
         Ok(output)
@@ -171,7 +216,7 @@ mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -1,15 +1,26 @@
 use crate::template::aoc_cli;
+use crate::template::commands::CommandError;
 use crate::Day;
-use std::process;
 
-pub fn handle(day: Day) {
+/// Files written by [`run`].
+#[derive(Debug, Clone)]
+pub struct DownloadReport {
+    pub day: Day,
+    pub input_path: String,
+    pub puzzle_path: String,
+}
+
+/// Downloads the input and puzzle description for `day` via aoc-cli.
+pub fn run(day: Day) -> Result<DownloadReport, CommandError> {
     if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+        return Err(CommandError::AocCliMissing);
     }
 
-    if let Err(e) = aoc_cli::download(day) {
-        eprintln!("failed to call aoc-cli: {e}");
-        process::exit(1);
-    };
+    aoc_cli::download(day)?;
+
+    Ok(DownloadReport {
+        day,
+        input_path: aoc_cli::get_input_path(day),
+        puzzle_path: aoc_cli::get_puzzle_path(day),
+    })
 }
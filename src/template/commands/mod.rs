@@ -1,5 +1,100 @@
+//! Handlers for the template's sub-commands.
+//!
+//! Every command exposes a `run` function that returns a report on success and a [`CommandError`] on failure.
+//! None of them print their result or exit the process, that is left to the caller (see `main.rs`).
+use std::fmt::Display;
+use std::io;
+
+use crate::template::aoc_cli::AocCommandError;
+use crate::Day;
+
 pub mod all;
 pub mod download;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
+
+/// An error which can be returned by any of the template commands.
+#[derive(Debug)]
+pub enum CommandError {
+    /// The `aoc` command is not installed or not callable.
+    AocCliMissing,
+    /// The `aoc` command was called, but failed.
+    AocCli(AocCommandError),
+    /// A file could not be created or written.
+    File {
+        action: &'static str,
+        path: String,
+        source: io::Error,
+    },
+    /// A child process could not be spawned or its output could not be read.
+    Process(io::Error),
+    /// The solution binary exited with a non-zero status.
+    SolutionFailed { day: Day, status: Option<i32> },
+}
+
+impl CommandError {
+    /// The exit code the command-line should terminate with when this error occurs.
+    ///
+    /// | Code | Error |
+    /// | :---: | :--- |
+    /// | `1` | [`CommandError::Process`] |
+    /// | `3` | [`CommandError::AocCliMissing`] |
+    /// | `4` | [`CommandError::AocCli`] |
+    /// | `5` | [`CommandError::File`] |
+    /// | _status of the child_ | [`CommandError::SolutionFailed`] |
+    ///
+    /// `2` is reserved for invalid command-line arguments.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CommandError::Process(_) => 1,
+            CommandError::AocCliMissing => 3,
+            CommandError::AocCli(_) => 4,
+            CommandError::File { .. } => 5,
+            CommandError::SolutionFailed { status, .. } => status.unwrap_or(1),
+        }
+    }
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::AocCliMissing => write!(
+                f,
+                "command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it."
+            ),
+            CommandError::AocCli(e) => write!(f, "failed to call aoc-cli: {e}"),
+            CommandError::File {
+                action,
+                path,
+                source,
+            } => write!(f, "failed to {action} \"{path}\": {source}"),
+            CommandError::Process(e) => write!(f, "failed to run child process: {e}"),
+            CommandError::SolutionFailed { day, status } => match status {
+                Some(status) => write!(f, "solution for day {day} exited with status {status}"),
+                None => write!(f, "solution for day {day} was terminated by a signal"),
+            },
+        }
+    }
+}
+
+impl std::error::Error for CommandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CommandError::File { source, .. } | CommandError::Process(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<AocCommandError> for CommandError {
+    fn from(e: AocCommandError) -> Self {
+        CommandError::AocCli(e)
+    }
+}
+
+impl From<io::Error> for CommandError {
+    fn from(e: io::Error) -> Self {
+        CommandError::Process(e)
+    }
+}
@@ -1,16 +1,24 @@
-use std::process;
-
 use crate::template::aoc_cli;
+use crate::template::commands::CommandError;
 use crate::Day;
 
-pub fn handle(day: Day) {
+/// Result of [`run`]. The puzzle description itself is displayed by aoc-cli.
+#[derive(Debug, Clone)]
+pub struct ReadReport {
+    pub day: Day,
+    pub puzzle_path: String,
+}
+
+/// Displays the puzzle description for `day` in the terminal via aoc-cli.
+pub fn run(day: Day) -> Result<ReadReport, CommandError> {
     if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+        return Err(CommandError::AocCliMissing);
     }
 
-    if let Err(e) = aoc_cli::read(day) {
-        eprintln!("failed to call aoc-cli: {e}");
-        process::exit(1);
-    };
+    aoc_cli::read(day)?;
+
+    Ok(ReadReport {
+        day,
+        puzzle_path: aoc_cli::get_puzzle_path(day),
+    })
 }
@@ -1,9 +1,9 @@
 use std::{
     fs::{File, OpenOptions},
     io::Write,
-    process,
 };
 
+use crate::template::commands::CommandError;
 use crate::Day;
 
 const MODULE_TEMPLATE: &str = r#"advent_of_code::solution!(DAY_NUMBER);
@@ -34,61 +34,60 @@ mod tests {
 }
 "#;
 
+/// Files created by [`run`].
+#[derive(Debug, Clone)]
+pub struct ScaffoldReport {
+    pub day: Day,
+    pub module_path: String,
+    pub input_path: String,
+    pub example_path: String,
+}
+
 fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
     OpenOptions::new().write(true).create_new(true).open(path)
 }
 
 fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create(true).open(path)
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
 }
 
-pub fn handle(day: Day) {
+/// Creates the solution module for `day` alongside empty input and example files.
+/// Fails without touching anything if the module file already exists.
+pub fn run(day: Day) -> Result<ScaffoldReport, CommandError> {
     let input_path = format!("data/inputs/{day}.txt");
     let example_path = format!("data/examples/{day}.txt");
     let module_path = format!("src/bin/{day}.rs");
 
-    let mut file = match safe_create_file(&module_path) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
+    let file_error = |action, path: &str| {
+        let path = path.to_string();
+        move |source| CommandError::File {
+            action,
+            path,
+            source,
         }
     };
 
-    match file.write_all(
+    let mut file =
+        safe_create_file(&module_path).map_err(file_error("create module file", &module_path))?;
+
+    file.write_all(
         MODULE_TEMPLATE
             .replace("DAY_NUMBER", &day.into_inner().to_string())
             .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
-        }
-    }
+    )
+    .map_err(file_error("write module contents to", &module_path))?;
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create input file: {e}");
-            process::exit(1);
-        }
-    }
-
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create example file: {e}");
-            process::exit(1);
-        }
-    }
+    create_file(&input_path).map_err(file_error("create input file", &input_path))?;
+    create_file(&example_path).map_err(file_error("create example file", &example_path))?;
 
-    println!("---");
-    println!("🎄 Type `cargo solve {}` to run your solution.", day);
+    Ok(ScaffoldReport {
+        day,
+        module_path,
+        input_path,
+        example_path,
+    })
 }
@@ -1,8 +1,31 @@
 use std::process::{Command, Stdio};
 
+use crate::template::commands::CommandError;
+use crate::viz;
 use crate::Day;
 
-pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
+/// Result of [`run`]. The solution output is forwarded to stdout / stderr while it runs.
+#[derive(Debug, Clone)]
+pub struct SolveReport {
+    pub day: Day,
+}
+
+/// Runs the solution binary for `day`.
+/// With `variants`, all registered variants of each part are benched against each other instead.
+/// With `verbose`, solutions report diagnostics such as memo statistics.
+/// With `visualize`, the visualization of the day is shown or written instead of solving it.
+/// `extra` arguments are passed on to the solution, e.g. for the commands registered with `solution!`.
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    day: Day,
+    release: bool,
+    time: bool,
+    submit_part: Option<u8>,
+    variants: bool,
+    verbose: bool,
+    visualize: Option<&viz::Options>,
+    extra: &[String],
+) -> Result<SolveReport, CommandError> {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if release {
@@ -20,12 +43,34 @@ pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
         cmd_args.push("--time".to_string());
     }
 
+    if variants {
+        cmd_args.push("--variants".to_string());
+    }
+
+    if verbose {
+        cmd_args.push("--verbose".to_string());
+    }
+
+    if let Some(options) = visualize {
+        cmd_args.extend(options.to_args());
+    }
+
+    cmd_args.extend_from_slice(extra);
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
-        .spawn()
-        .unwrap();
+        .spawn()?;
+
+    let status = cmd.wait()?;
+
+    if !status.success() {
+        return Err(CommandError::SolutionFailed {
+            day,
+            status: status.code(),
+        });
+    }
 
-    cmd.wait().unwrap();
+    Ok(SolveReport { day })
 }
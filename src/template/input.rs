@@ -0,0 +1,136 @@
+/// Sanity checks for puzzle inputs, run before a solution gets to see them.
+use std::fmt::Display;
+
+/// A problem found in an input file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputIssue {
+    /// The file contains no data at all, e.g. the placeholder created by `scaffold`.
+    Empty,
+    /// Lines end with `\r\n`. Normalised to `\n`.
+    CrlfLineEndings,
+    /// The last line is not terminated by a newline. Normalised by appending one.
+    MissingTrailingNewline,
+    /// The file looks like an HTML page, e.g. an error page saved by a failed download.
+    Html,
+}
+
+impl InputIssue {
+    /// Returns `true` if the issue is fixed by [`normalise`].
+    pub fn is_normalised(self) -> bool {
+        matches!(
+            self,
+            InputIssue::CrlfLineEndings | InputIssue::MissingTrailingNewline
+        )
+    }
+}
+
+impl Display for InputIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputIssue::Empty => write!(f, "input is empty. Did you forget to download it?"),
+            InputIssue::CrlfLineEndings => {
+                write!(f, "input has CRLF line endings, converted them to LF.")
+            }
+            InputIssue::MissingTrailingNewline => {
+                write!(f, "input has no trailing newline, appended one.")
+            }
+            InputIssue::Html => write!(
+                f,
+                "input looks like HTML. The download might have failed, check your session cookie."
+            ),
+        }
+    }
+}
+
+/// Lists all issues found in `input`.
+#[must_use]
+pub fn inspect(input: &str) -> Vec<InputIssue> {
+    let mut issues = vec![];
+
+    if input.trim().is_empty() {
+        issues.push(InputIssue::Empty);
+        return issues;
+    }
+
+    if input.contains("\r\n") {
+        issues.push(InputIssue::CrlfLineEndings);
+    }
+
+    if !input.ends_with('\n') {
+        issues.push(InputIssue::MissingTrailingNewline);
+    }
+
+    if looks_like_html(input) {
+        issues.push(InputIssue::Html);
+    }
+
+    issues
+}
+
+/// Fixes the issues in `input` that can be fixed and returns all issues that were found.
+#[must_use]
+pub fn normalise(input: String) -> (String, Vec<InputIssue>) {
+    let issues = inspect(&input);
+
+    let mut input = if issues.contains(&InputIssue::CrlfLineEndings) {
+        input.replace("\r\n", "\n")
+    } else {
+        input
+    };
+
+    if issues.contains(&InputIssue::MissingTrailingNewline) {
+        input.push('\n');
+    }
+
+    (input, issues)
+}
+
+fn looks_like_html(input: &str) -> bool {
+    let head = input.trim_start().get(..64).unwrap_or(input.trim_start());
+    let head = head.to_ascii_lowercase();
+
+    ["<!doctype html", "<html", "<head", "<body"]
+        .iter()
+        .any(|tag| head.starts_with(tag))
+        || input.contains("Puzzle inputs differ by user")
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{inspect, normalise, InputIssue};
+
+    #[test]
+    fn clean_input() {
+        assert_eq!(inspect("1 2 3\n4 5 6\n"), vec![]);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(inspect(""), vec![InputIssue::Empty]);
+        assert_eq!(inspect("\n\n"), vec![InputIssue::Empty]);
+    }
+
+    #[test]
+    fn html_input() {
+        let issues = inspect("<!DOCTYPE html>\n<html lang=\"en-us\">\n");
+        assert_eq!(issues, vec![InputIssue::Html]);
+
+        let issues =
+            inspect("Puzzle inputs differ by user.  Please log in to get your puzzle input.\n");
+        assert_eq!(issues, vec![InputIssue::Html]);
+    }
+
+    #[test]
+    fn normalises_line_endings() {
+        let (input, issues) = normalise("a\r\nb".to_string());
+        assert_eq!(input, "a\nb\n");
+        assert_eq!(
+            issues,
+            vec![
+                InputIssue::CrlfLineEndings,
+                InputIssue::MissingTrailingNewline
+            ]
+        );
+        assert!(issues.iter().all(|issue| issue.is_normalised()));
+    }
+}
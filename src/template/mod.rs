@@ -1,46 +1,168 @@
 use crate::Day;
-use std::{env, fs};
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::{env, fs, io};
 
 pub mod aoc_cli;
 pub mod commands;
+pub mod input;
 pub mod readme_benchmarks;
 pub mod runner;
+pub mod spans;
+
+pub use spans::span;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
+/// An error which can be returned when reading a data file.
+#[derive(Debug)]
+pub struct ReadFileError {
+    pub path: PathBuf,
+    pub source: io::Error,
+}
+
+impl std::error::Error for ReadFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl Display for ReadFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "could not open \"{}\": {}",
+            self.path.display(),
+            self.source
+        )
+    }
+}
+
+fn data_file_path(folder: &str, file_name: &str) -> PathBuf {
+    let cwd = env::current_dir().unwrap_or_default();
+    cwd.join("data").join(folder).join(file_name)
+}
+
+fn try_read_path(filepath: PathBuf) -> Result<String, ReadFileError> {
+    fs::read_to_string(&filepath).map_err(|source| ReadFileError {
+        path: filepath,
+        source,
+    })
+}
+
+/// Helper function that reads a text file to a string.
+/// Returns an error naming the full path if the file can't be read.
+pub fn try_read_file(folder: &str, day: Day) -> Result<String, ReadFileError> {
+    try_read_path(data_file_path(folder, &format!("{day}.txt")))
+}
+
+/// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
+/// Returns an error naming the full path if the file can't be read.
+pub fn try_read_file_part(folder: &str, day: Day, part: u8) -> Result<String, ReadFileError> {
+    try_read_path(data_file_path(folder, &format!("{day}-{part}.txt")))
+}
+
 /// Helper function that reads a text file to a string.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    try_read_file(folder, day).unwrap_or_else(|e| panic!("{e}"))
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
-        .join(format!("{day}-{part}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    try_read_file_part(folder, day, part).unwrap_or_else(|e| panic!("{e}"))
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
+///
+/// Alternative implementations of a part can be registered after the day.
+/// `cargo solve <day> --variants` benches them next to the part and checks that all answers match.
+///
+/// ```ignore
+/// advent_of_code::solution!(12, part_one: [part_one_brute_force]);
+/// ```
+///
+/// A function drawing frames of the solution can be registered last, it is run instead of the parts
+/// by `cargo solve <day> --visualize`, see [`crate::viz`].
+///
+/// ```ignore
+/// advent_of_code::solution!(16, visualize: visualize);
+/// ```
+///
+/// Extra commands of a day are registered with their flag, see [`runner::Command`].
+/// `cargo solve <day> -- <flag> [args]` runs the command instead of the parts.
+///
+/// ```ignore
+/// advent_of_code::solution!(7, commands: ["--explain" => explain]);
+/// ```
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
+    (
+        $day:expr
+        $(, part_one: [$($one:path),* $(,)?])?
+        $(, part_two: [$($two:path),* $(,)?])?
+        $(, visualize: $visualize:path)?
+        $(, commands: [$($flag:literal => $command:path),* $(,)?])?
+        $(,)?
+    ) => {
         /// The current day.
         const DAY: advent_of_code::Day = advent_of_code::day!($day);
 
         fn main() {
             use advent_of_code::template::runner::*;
-            let input = advent_of_code::template::read_file("inputs", DAY);
+            let input = match advent_of_code::template::try_read_file("inputs", DAY) {
+                Ok(input) => prepare_input(input),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            };
+
+            match advent_of_code::viz::Options::from_env() {
+                Ok(Some(options)) => {
+                    let visualize: Option<Visualize> = None $(.or(Some($visualize)))?;
+                    run_visualization(visualize, &input, DAY, options);
+                    return;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(2);
+                }
+            }
+
+            let commands: &[(&str, Command)] = &[$($(($flag, $command as Command),)*)?];
+            if run_command(commands, &input) {
+                return;
+            }
+
+            if std::env::args().any(|x| x == "--variants") {
+                let part_one_matches = run_variants(
+                    &[
+                        ("part_one", &part_one as &dyn Fn(&str) -> _),
+                        $($((stringify!($one), &$one as &dyn Fn(&str) -> _),)*)?
+                    ],
+                    input.as_str(),
+                    1,
+                );
+                println!();
+                let part_two_matches = run_variants(
+                    &[
+                        ("part_two", &part_two as &dyn Fn(&str) -> _),
+                        $($((stringify!($two), &$two as &dyn Fn(&str) -> _),)*)?
+                    ],
+                    input.as_str(),
+                    2,
+                );
+                if !(part_one_matches && part_two_matches) {
+                    std::process::exit(1);
+                }
+                return;
+            }
+
             run_part(part_one, &input, DAY, 1);
             run_part(part_two, &input, DAY, 2);
         }
@@ -1,6 +1,9 @@
 /// Encapsulates code that interacts with solution functions.
-use crate::template::{aoc_cli, ANSI_ITALIC, ANSI_RESET};
+use crate::memo::{self, MemoStats};
+use crate::template::{aoc_cli, input, spans, ANSI_ITALIC, ANSI_RESET};
+use crate::viz::{self, Visualizer};
 use crate::Day;
+use std::error::Error;
 use std::fmt::Display;
 use std::io::{stdout, Write};
 use std::process::Output;
@@ -9,40 +12,223 @@ use std::{cmp, env, process};
 
 use super::ANSI_BOLD;
 
+/// Checks the puzzle input before it is handed to the solution.
+/// Fixable issues (line endings, trailing newline) are normalised, all issues are reported on stderr.
+#[must_use]
+pub fn prepare_input(input: String) -> String {
+    let (input, issues) = input::normalise(input);
+
+    for issue in issues {
+        if issue.is_normalised() {
+            eprintln!("{ANSI_ITALIC}Note: {issue}{ANSI_RESET}");
+        } else {
+            eprintln!("{ANSI_BOLD}Warning:{ANSI_RESET} {issue}");
+        }
+    }
+
+    input
+}
+
 pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let timed = run_timed(func, input, |result| print_result(result, &part_str, ""));
+
+    print_result(
+        &timed.result,
+        &part_str,
+        &format_duration(&timed.duration, timed.samples),
+    );
+
+    for line in timed.spans.format(timed.samples) {
+        println!("  {ANSI_ITALIC}{line}{ANSI_RESET}");
+    }
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    for stats in &timed.memos {
+        println!("  {ANSI_ITALIC}{stats}{ANSI_RESET}");
+    }
 
-    if let Some(result) = result {
+    if let Some(result) = timed.result {
         submit_result(result, day, part);
     }
 }
 
+/// A named implementation of a solution part, as registered with `solution!`.
+pub type Variant<'a, I, T> = (&'a str, &'a dyn Fn(I) -> Option<T>);
+
+/// Benches every variant of a part and prints a table comparing their timings.
+/// The first variant is the reference the others are compared against.
+///
+/// Returns `false` if any variant's answer differs from the reference.
+pub fn run_variants<I: Clone, T: Display + PartialEq>(
+    variants: &[Variant<I, T>],
+    input: I,
+    part: u8,
+) -> bool {
+    let mut rows: Vec<(&str, Option<T>, Duration, u128)> = vec![];
+
+    for (name, func) in variants {
+        print!("Part {part} / {name}:");
+
+        let timer = Instant::now();
+        let result = func(input.clone());
+        let base_time = timer.elapsed();
+
+        let (duration, samples) = bench(func, input.clone(), &base_time);
+        print!("\r\x1b[2K");
+        let _ = stdout().flush();
+
+        rows.push((name, result, duration, samples));
+    }
+
+    let Some((_, reference, reference_duration, _)) = rows.first() else {
+        return true;
+    };
+
+    let format_result = |result: &Option<T>| match result {
+        Some(result) => result.to_string(),
+        None => "✖".into(),
+    };
+
+    println!("{ANSI_BOLD}Part {part}{ANSI_RESET}");
+    println!("| Variant | Result | Time | Relative |");
+    println!("| :--- | :---: | :---: | :---: |");
+
+    let mut all_match = true;
+
+    for (name, result, duration, samples) in &rows {
+        let matches = result == reference;
+        all_match &= matches;
+
+        #[allow(clippy::cast_precision_loss)]
+        let relative = duration.as_nanos() as f64 / reference_duration.as_nanos().max(1) as f64;
+
+        println!(
+            "| {name} | {}{} | `{duration:.1?}` @ {samples} samples | {relative:.2}x |",
+            format_result(result),
+            if matches { "" } else { " ≠" },
+        );
+    }
+
+    if !all_match {
+        eprintln!(
+            "{ANSI_BOLD}Error:{ANSI_RESET} variants of part {part} disagree, expected {}.",
+            format_result(reference)
+        );
+    }
+
+    all_match
+}
+
+/// The signature of a day's `visualize` function, as registered with `solution!`.
+pub type Visualize = fn(&str, &mut Visualizer);
+
+/// Runs the visualization of a day and reports where its frames went.
+/// Exits if the day has no visualization or its frames could not be written.
+pub fn run_visualization(
+    visualize: Option<Visualize>,
+    input: &str,
+    day: Day,
+    options: viz::Options,
+) {
+    let Some(visualize) = visualize else {
+        eprintln!("{ANSI_BOLD}Error:{ANSI_RESET} day {day} has no visualization.");
+        process::exit(1);
+    };
+
+    let mut visualizer = Visualizer::new(options);
+    visualize(input, &mut visualizer);
+
+    match visualizer.finish() {
+        Ok(report) => {
+            println!("{ANSI_ITALIC}{} frames{ANSI_RESET}", report.frames);
+            if let Some(path) = report.animation {
+                println!("Wrote animation to \"{}\"", path.display());
+            }
+        }
+        Err(e) => {
+            eprintln!("{ANSI_BOLD}Error:{ANSI_RESET} failed to write visualization: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/// The signature of a day's extra command, as registered with `solution!`.
+/// It receives the input and the arguments after its flag.
+pub type Command = fn(&str, &[String]) -> Result<(), Box<dyn Error>>;
+
+/// Runs the first registered command whose flag was passed, returns whether one was run.
+/// Exits if the command fails.
+pub fn run_command(commands: &[(&str, Command)], input: &str) -> bool {
+    let args: Vec<String> = env::args().collect();
+
+    let found = args.iter().enumerate().find_map(|(i, arg)| {
+        commands
+            .iter()
+            .find(|(flag, _)| flag == arg)
+            .map(|(flag, command)| (i, *flag, command))
+    });
+
+    let Some((i, flag, command)) = found else {
+        return false;
+    };
+
+    if let Err(e) = command(input, &args[i + 1..]) {
+        eprintln!("{ANSI_BOLD}Error:{ANSI_RESET} {flag} failed: {e}");
+        process::exit(1);
+    }
+
+    true
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Clone, T>(
-    func: impl Fn(I) -> T,
-    input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+///
+/// Spans recorded while benching are returned aggregated over all samples.
+/// With `--verbose`, memo statistics of the first execution are returned as well.
+fn run_timed<I: Clone, T>(func: impl Fn(I) -> T, input: I, hook: impl Fn(&T)) -> Timed<T> {
+    let verbose = env::args().any(|x| x == "--verbose");
+
+    if verbose {
+        memo::start_recording();
+    }
+
     let timer = Instant::now();
     let result = func(input.clone());
     let base_time = timer.elapsed();
 
+    let memos = if verbose {
+        memo::finish_recording()
+    } else {
+        vec![]
+    };
+
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+    let (duration, samples, spans) = if env::args().any(|x| x == "--time") {
+        spans::start_recording();
+        let (duration, samples) = bench(func, input, &base_time);
+        (duration, samples, spans::finish_recording())
     } else {
-        (base_time, 1)
+        (base_time, 1, spans::SpanTree::default())
     };
 
-    (result, run.0, run.1)
+    Timed {
+        result,
+        duration,
+        samples,
+        spans,
+        memos,
+    }
+}
+
+struct Timed<T> {
+    result: T,
+    duration: Duration,
+    samples: u128,
+    spans: spans::SpanTree,
+    memos: Vec<MemoStats>,
 }
 
 fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
@@ -51,13 +237,8 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    let bench_iterations =
+        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
 
     let mut timers: Vec<Duration> = vec![];
 
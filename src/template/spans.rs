@@ -0,0 +1,185 @@
+/// Hierarchical timing of code sections inside solutions.
+/// Durations are only recorded while the runner benchmarks a part (`--time`).
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    static RECORDER: RefCell<SpanTree> = RefCell::new(SpanTree::default());
+}
+
+/// Marks the start of a timed section that ends when the returned guard is dropped.
+/// When not benchmarking, this is a single thread-local flag check.
+///
+/// Bind the guard to a named variable, `let _ = span(..)` drops it immediately.
+///
+/// ```
+/// let _t = advent_of_code::template::span("flood_fill");
+/// ```
+#[must_use = "the span ends when the guard is dropped"]
+pub fn span(name: &'static str) -> SpanGuard {
+    if !ENABLED.with(Cell::get) {
+        return SpanGuard { start: None };
+    }
+
+    RECORDER.with(|recorder| recorder.borrow_mut().enter(name));
+
+    SpanGuard {
+        start: Some(Instant::now()),
+    }
+}
+
+/// Guard returned by [`span`].
+pub struct SpanGuard {
+    start: Option<Instant>,
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            let elapsed = start.elapsed();
+            RECORDER.with(|recorder| recorder.borrow_mut().exit(elapsed));
+        }
+    }
+}
+
+/// Clears previous recordings and starts recording spans on the current thread.
+pub fn start_recording() {
+    RECORDER.with(|recorder| *recorder.borrow_mut() = SpanTree::default());
+    ENABLED.with(|enabled| enabled.set(true));
+}
+
+/// Stops recording and returns everything that was recorded since [`start_recording`].
+pub fn finish_recording() -> SpanTree {
+    ENABLED.with(|enabled| enabled.set(false));
+    RECORDER.with(|recorder| recorder.take())
+}
+
+#[derive(Debug, Clone)]
+struct SpanNode {
+    name: &'static str,
+    children: Vec<usize>,
+    total: Duration,
+    count: u64,
+}
+
+/// Durations of all recorded spans, aggregated by their position in the call tree.
+#[derive(Debug, Clone, Default)]
+pub struct SpanTree {
+    nodes: Vec<SpanNode>,
+    roots: Vec<usize>,
+    stack: Vec<usize>,
+}
+
+impl SpanTree {
+    fn enter(&mut self, name: &'static str) {
+        let siblings = match self.stack.last() {
+            Some(&parent) => &self.nodes[parent].children,
+            None => &self.roots,
+        };
+
+        let index = match siblings.iter().find(|&&i| self.nodes[i].name == name) {
+            Some(&index) => index,
+            None => {
+                let index = self.nodes.len();
+                self.nodes.push(SpanNode {
+                    name,
+                    children: vec![],
+                    total: Duration::ZERO,
+                    count: 0,
+                });
+
+                match self.stack.last() {
+                    Some(&parent) => self.nodes[parent].children.push(index),
+                    None => self.roots.push(index),
+                }
+
+                index
+            }
+        };
+
+        self.stack.push(index);
+    }
+
+    fn exit(&mut self, elapsed: Duration) {
+        if let Some(index) = self.stack.pop() {
+            let node = &mut self.nodes[index];
+            node.total += elapsed;
+            node.count += 1;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Renders the tree with durations averaged over `samples` runs, one line per span.
+    pub fn format(&self, samples: u128) -> Vec<String> {
+        let mut lines = vec![];
+        self.format_level(&self.roots, "", samples.max(1), &mut lines);
+        lines
+    }
+
+    fn format_level(&self, level: &[usize], prefix: &str, samples: u128, lines: &mut Vec<String>) {
+        for (i, &index) in level.iter().enumerate() {
+            let node = &self.nodes[index];
+            let is_last = i + 1 == level.len();
+
+            #[allow(clippy::cast_possible_truncation)]
+            let average = Duration::from_nanos((node.total.as_nanos() / samples) as u64);
+            let calls = u128::from(node.count) / samples;
+
+            let branch = if is_last { "└─" } else { "├─" };
+            let calls = if calls > 1 {
+                format!(" ×{calls}")
+            } else {
+                String::new()
+            };
+
+            lines.push(format!(
+                "{prefix}{branch} {} {average:.1?}{calls}",
+                node.name
+            ));
+
+            let child_prefix = format!("{prefix}{}", if is_last { "   " } else { "│  " });
+            self.format_level(&node.children, &child_prefix, samples, lines);
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{finish_recording, span, start_recording};
+
+    #[test]
+    fn disabled_by_default() {
+        {
+            let _t = span("outer");
+        }
+        assert!(finish_recording().is_empty());
+    }
+
+    #[test]
+    fn aggregates_nested_spans() {
+        start_recording();
+
+        for _ in 0..2 {
+            let _outer = span("outer");
+            for _ in 0..3 {
+                let _inner = span("inner");
+            }
+        }
+        {
+            let _t = span("second");
+        }
+
+        let tree = finish_recording();
+        let lines = tree.format(2);
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("├─ outer "));
+        assert!(lines[1].starts_with("│  └─ inner "));
+        assert!(lines[1].ends_with(" ×3"));
+        assert!(lines[2].starts_with("└─ second "));
+    }
+}
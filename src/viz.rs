@@ -0,0 +1,697 @@
+/// Renders grids as frames, either animated in the terminal or written to image files.
+///
+/// Solutions register a `visualize(input, &mut Visualizer)` function with `solution!` and push a frame
+/// for every step they want to show. `cargo solve DD --visualize` plays the frames in the terminal,
+/// `--visualize-to <dir>` writes a PPM file per frame and an animated PNG of all of them.
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use crate::grid::Grid;
+
+/// Pixels per cell side in image output.
+const SCALE: usize = 4;
+pub const DEFAULT_FPS: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GRAY: Rgb = Rgb(110, 110, 110);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(80, 200, 80);
+    pub const BLUE: Rgb = Rgb(38, 139, 210);
+    pub const YELLOW: Rgb = Rgb(250, 210, 40);
+
+    /// The colour at `t` (clamped to `0.0..=1.0`) on a dark blue to yellow ramp, for heat maps.
+    pub fn heat(t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |from: f64, to: f64| (from + (to - from) * t).round() as u8;
+
+        Rgb(
+            channel(20.0, 250.0),
+            channel(30.0, 210.0),
+            channel(90.0, 40.0),
+        )
+    }
+}
+
+/// What a single cell looks like: a character with optional foreground and background colours.
+/// Image output only has room for one colour per cell, it uses the background, or else the foreground
+/// of a visible character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Glyph {
+    pub ch: char,
+    pub fg: Option<Rgb>,
+    pub bg: Option<Rgb>,
+}
+
+impl Glyph {
+    pub const EMPTY: Glyph = Glyph::new(' ');
+
+    pub const fn new(ch: char) -> Self {
+        Self {
+            ch,
+            fg: None,
+            bg: None,
+        }
+    }
+
+    /// A blank cell filled with `color`.
+    pub const fn fill(color: Rgb) -> Self {
+        Self {
+            ch: ' ',
+            fg: None,
+            bg: Some(color),
+        }
+    }
+
+    #[must_use]
+    pub const fn fg(mut self, color: Rgb) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    #[must_use]
+    pub const fn bg(mut self, color: Rgb) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    /// The colour of the cell in image output.
+    pub fn pixel(&self) -> Rgb {
+        match (self.bg, self.fg) {
+            (Some(bg), _) => bg,
+            (None, Some(fg)) if !self.ch.is_whitespace() => fg,
+            _ => Rgb::BLACK,
+        }
+    }
+}
+
+impl From<char> for Glyph {
+    fn from(ch: char) -> Self {
+        Glyph::new(ch)
+    }
+}
+
+/// A single picture, one glyph per cell.
+pub type Frame = Grid<Glyph>;
+
+/// The frame as text with 24-bit ANSI colour escapes, one line per row.
+pub fn to_ansi(frame: &Frame) -> String {
+    let mut out = String::new();
+
+    for row in frame.rows() {
+        let mut current: (Option<Rgb>, Option<Rgb>) = (None, None);
+
+        for glyph in row {
+            if (glyph.fg, glyph.bg) != current {
+                out.push_str("\x1b[0m");
+                if let Some(Rgb(r, g, b)) = glyph.fg {
+                    out.push_str(&format!("\x1b[38;2;{r};{g};{b}m"));
+                }
+                if let Some(Rgb(r, g, b)) = glyph.bg {
+                    out.push_str(&format!("\x1b[48;2;{r};{g};{b}m"));
+                }
+                current = (glyph.fg, glyph.bg);
+            }
+            out.push(glyph.ch);
+        }
+
+        out.push_str("\x1b[0m\n");
+    }
+
+    out
+}
+
+/// Raw RGB pixels of the frame, each cell drawn as a `scale` by `scale` square.
+fn pixels(frame: &Grid<Rgb>, scale: usize) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(frame.width() * frame.height() * scale * scale * 3);
+
+    for row in frame.rows() {
+        let mut line = Vec::with_capacity(row.len() * scale * 3);
+        for &Rgb(r, g, b) in row {
+            for _ in 0..scale {
+                line.extend([r, g, b]);
+            }
+        }
+
+        for _ in 0..scale {
+            pixels.extend_from_slice(&line);
+        }
+    }
+
+    pixels
+}
+
+/// Writes the frame as a binary PPM image.
+pub fn write_ppm(frame: &Frame, scale: usize, mut writer: impl Write) -> io::Result<()> {
+    let colors = frame.map(Glyph::pixel);
+
+    write!(
+        writer,
+        "P6\n{} {}\n255\n",
+        frame.width() * scale,
+        frame.height() * scale
+    )?;
+    writer.write_all(&pixels(&colors, scale))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Where the frames of a visualization go.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    /// Animated in the terminal.
+    Terminal,
+    /// Written as `frame_NNNN.ppm` files and an `animation.png` to a directory.
+    Files(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub target: Target,
+    pub fps: f64,
+}
+
+impl Options {
+    /// Takes `--visualize`, `--visualize-to <dir>` and `--fps <n>` out of `args`.
+    /// Returns [`None`] if no visualization was requested.
+    ///
+    /// # Errors
+    /// If a flag misses its value or `--fps` is not a positive number.
+    pub fn from_args(args: &mut pico_args::Arguments) -> Result<Option<Self>, pico_args::Error> {
+        let terminal = args.contains("--visualize");
+        let dir: Option<String> = args.opt_value_from_str("--visualize-to")?;
+        let fps = args.opt_value_from_fn("--fps", parse_fps)?;
+
+        let target = match dir {
+            Some(dir) => Target::Files(dir.into()),
+            None if terminal => Target::Terminal,
+            None => return Ok(None),
+        };
+
+        Ok(Some(Self {
+            target,
+            fps: fps.unwrap_or(DEFAULT_FPS),
+        }))
+    }
+
+    /// [`Options::from_args`] for the arguments of the process.
+    pub fn from_env() -> Result<Option<Self>, pico_args::Error> {
+        Self::from_args(&mut pico_args::Arguments::from_env())
+    }
+
+    /// The arguments that [`Options::from_args`] reads back.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = match &self.target {
+            Target::Terminal => vec!["--visualize".to_string()],
+            Target::Files(dir) => vec!["--visualize-to".to_string(), dir.display().to_string()],
+        };
+
+        args.push("--fps".to_string());
+        args.push(self.fps.to_string());
+
+        args
+    }
+}
+
+fn parse_fps(value: &str) -> Result<f64, &'static str> {
+    match value.parse::<f64>() {
+        Ok(fps) if fps > 0.0 && fps.is_finite() => Ok(fps),
+        _ => Err("expected a positive number of frames per second"),
+    }
+}
+
+/// Result of [`Visualizer::finish`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisualizeReport {
+    pub frames: usize,
+    /// The animated PNG, when writing to files.
+    pub animation: Option<PathBuf>,
+}
+
+/// Collects the frames of a visualization and shows or writes them.
+pub struct Visualizer {
+    options: Options,
+    frames: usize,
+    /// Frames kept for the animation at the end, one colour per cell.
+    images: Vec<Grid<Rgb>>,
+    error: Option<io::Error>,
+}
+
+impl Visualizer {
+    pub fn new(options: Options) -> Self {
+        Self {
+            options,
+            frames: 0,
+            images: vec![],
+            error: None,
+        }
+    }
+
+    /// Shows a grid, drawing each cell with `f`.
+    pub fn show<T>(&mut self, grid: &Grid<T>, f: impl FnMut(&T) -> Glyph) {
+        self.frame(grid.map(f));
+    }
+
+    /// Shows a frame. In the terminal, waits for the frame duration afterwards.
+    /// Write errors are reported by [`Visualizer::finish`].
+    pub fn frame(&mut self, frame: Frame) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let index = self.frames;
+        self.frames += 1;
+
+        let result = match &self.options.target {
+            Target::Terminal => {
+                let mut stdout = io::stdout().lock();
+                let clear = if index == 0 {
+                    "\x1b[2J\x1b[H"
+                } else {
+                    "\x1b[H"
+                };
+                let result =
+                    write!(stdout, "{clear}{}", to_ansi(&frame)).and_then(|_| stdout.flush());
+                thread::sleep(Duration::from_secs_f64(1.0 / self.options.fps));
+                result
+            }
+            Target::Files(dir) => {
+                self.images.push(frame.map(Glyph::pixel));
+
+                fs::create_dir_all(dir)
+                    .and_then(|_| File::create(dir.join(format!("frame_{index:04}.ppm"))))
+                    .and_then(|file| write_ppm(&frame, SCALE, BufWriter::new(file)))
+            }
+        };
+
+        self.error = result.err();
+    }
+
+    /// Writes the animation and returns the first error that occurred.
+    pub fn finish(self) -> io::Result<VisualizeReport> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+
+        let animation = match &self.options.target {
+            Target::Files(dir) if !self.images.is_empty() => {
+                let path = dir.join("animation.png");
+                let mut writer = BufWriter::new(File::create(&path)?);
+                write_apng(&self.images, SCALE, self.options.fps, &mut writer)?;
+                writer.flush()?;
+                Some(path)
+            }
+            _ => None,
+        };
+
+        Ok(VisualizeReport {
+            frames: self.frames,
+            animation,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Writes `frames` as an animated PNG that loops forever. All frames must have the same size.
+/// Viewers without animation support show the first frame.
+pub fn write_apng(
+    frames: &[Grid<Rgb>],
+    scale: usize,
+    fps: f64,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let first = frames
+        .first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no frames"))?;
+    let (width, height) = (
+        (first.width() * scale) as u32,
+        (first.height() * scale) as u32,
+    );
+
+    writer.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+    let mut header = vec![];
+    header.extend(width.to_be_bytes());
+    header.extend(height.to_be_bytes());
+    // 8 bit RGB, default compression and filtering, no interlacing.
+    header.extend([8, 2, 0, 0, 0]);
+    write_chunk(&mut writer, b"IHDR", &header)?;
+
+    let mut animation_control = vec![];
+    animation_control.extend((frames.len() as u32).to_be_bytes());
+    animation_control.extend(0_u32.to_be_bytes());
+    write_chunk(&mut writer, b"acTL", &animation_control)?;
+
+    let delay_ms = (1000.0 / fps).round().clamp(1.0, u16::MAX as f64) as u16;
+    let mut sequence = 0_u32;
+
+    for (i, frame) in frames.iter().enumerate() {
+        let mut frame_control = vec![];
+        frame_control.extend(sequence.to_be_bytes());
+        frame_control.extend(width.to_be_bytes());
+        frame_control.extend(height.to_be_bytes());
+        frame_control.extend(0_u32.to_be_bytes());
+        frame_control.extend(0_u32.to_be_bytes());
+        frame_control.extend(delay_ms.to_be_bytes());
+        frame_control.extend(1000_u16.to_be_bytes());
+        // Keep the frame when the next one is drawn, draw the next one over it.
+        frame_control.extend([0, 0]);
+        write_chunk(&mut writer, b"fcTL", &frame_control)?;
+        sequence += 1;
+
+        let data = zlib(&scanlines(frame, scale), width as usize * 3 + 1);
+
+        if i == 0 {
+            write_chunk(&mut writer, b"IDAT", &data)?;
+        } else {
+            let mut frame_data = sequence.to_be_bytes().to_vec();
+            frame_data.extend(data);
+            write_chunk(&mut writer, b"fdAT", &frame_data)?;
+            sequence += 1;
+        }
+    }
+
+    write_chunk(&mut writer, b"IEND", &[])
+}
+
+/// The image rows of a frame, each prefixed with PNG filter type `0`.
+fn scanlines(frame: &Grid<Rgb>, scale: usize) -> Vec<u8> {
+    let stride = frame.width() * scale * 3;
+
+    pixels(frame, scale)
+        .chunks(stride)
+        .flat_map(|row| std::iter::once(0).chain(row.iter().copied()))
+        .collect()
+}
+
+fn write_chunk(mut writer: impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    let mut crc = Crc32::new();
+    crc.update(kind);
+    crc.update(data);
+
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    writer.write_all(&crc.finish().to_be_bytes())
+}
+
+struct Crc32(u32);
+
+impl Crc32 {
+    fn new() -> Self {
+        Self(0xFFFF_FFFF)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u32;
+            for _ in 0..8 {
+                let mask = (self.0 & 1).wrapping_neg();
+                self.0 = (self.0 >> 1) ^ (0xEDB8_8320 & mask);
+            }
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+
+    for chunk in bytes.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+
+    (b << 16) | a
+}
+
+/* -------------------------------------------------------------------------- */
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Compresses image `data` with rows of `row_length` bytes into a zlib stream.
+///
+/// Uses a single deflate block with the fixed Huffman codes. Matches are only searched one pixel to
+/// the left and one row up, which is where repetition is in scaled-up pixel art.
+fn zlib(data: &[u8], row_length: usize) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+    bits.bytes.extend([0x78, 0x01]);
+
+    // Final block, fixed Huffman codes.
+    bits.write(1, 1);
+    bits.write(1, 2);
+
+    let mut i = 0;
+    while i < data.len() {
+        let (length, distance) = [3, row_length]
+            .into_iter()
+            .filter(|&distance| distance <= i && distance <= 32768)
+            .map(|distance| {
+                let length = (0..258.min(data.len() - i))
+                    .take_while(|&k| data[i + k] == data[i + k - distance])
+                    .count();
+                (length, distance)
+            })
+            .max()
+            .unwrap_or((0, 0));
+
+        if length >= 3 {
+            bits.length(length as u16);
+            bits.distance(distance as u16);
+            i += length;
+        } else {
+            bits.literal(data[i] as u16);
+            i += 1;
+        }
+    }
+
+    bits.literal(256);
+    let mut bytes = bits.finish();
+    bytes.extend(adler32(data).to_be_bytes());
+
+    bytes
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    /// Writes the lowest `count` bits of `value`, least significant bit first.
+    fn write(&mut self, value: u32, count: u32) {
+        self.buffer |= value << self.count;
+        self.count += count;
+
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Writes a Huffman code, which is stored most significant bit first.
+    fn code(&mut self, code: u32, count: u32) {
+        self.write(code.reverse_bits() >> (32 - count), count);
+    }
+
+    fn literal(&mut self, symbol: u16) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    fn length(&mut self, length: u16) {
+        let index = LENGTH_BASE.partition_point(|&base| base <= length) - 1;
+        self.literal(257 + index as u16);
+        self.write(
+            (length - LENGTH_BASE[index]) as u32,
+            LENGTH_EXTRA[index] as u32,
+        );
+    }
+
+    fn distance(&mut self, distance: u16) {
+        let index = DISTANCE_BASE.partition_point(|&base| base <= distance) - 1;
+        self.code(index as u32, 5);
+        self.write(
+            (distance - DISTANCE_BASE[index]) as u32,
+            DISTANCE_EXTRA[index] as u32,
+        );
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        adler32, to_ansi, write_apng, write_ppm, zlib, Crc32, Glyph, Options, Rgb, Target,
+    };
+    use crate::grid::Grid;
+
+    #[test]
+    fn checksums() {
+        let mut crc = Crc32::new();
+        crc.update(b"123456789");
+        assert_eq!(crc.finish(), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn ppm() {
+        let frame = Grid::from_vec(2, 1, vec![Glyph::fill(Rgb::RED), Glyph::new('#')]).unwrap();
+        let mut bytes = vec![];
+        write_ppm(&frame, 2, &mut bytes).unwrap();
+
+        let (header, pixels) = bytes.split_at(11);
+        assert_eq!(header, b"P6\n4 2\n255\n");
+        assert_eq!(pixels.len(), 4 * 2 * 3);
+        assert_eq!(&pixels[..6], &[220, 50, 47, 220, 50, 47]);
+        assert_eq!(
+            &pixels[6..12],
+            &[0, 0, 0, 0, 0, 0],
+            "no colour without fg or bg"
+        );
+    }
+
+    #[test]
+    fn ansi() {
+        let frame = Grid::from_vec(
+            2,
+            1,
+            vec![Glyph::new('a').fg(Rgb(1, 2, 3)), Glyph::new('b')],
+        )
+        .unwrap();
+
+        assert_eq!(to_ansi(&frame), "\x1b[0m\x1b[38;2;1;2;3ma\x1b[0mb\x1b[0m\n");
+    }
+
+    #[test]
+    fn zlib_bytes() {
+        // Decompresses to the input with any inflater: literals, then back references 3 bytes back.
+        assert_eq!(
+            zlib(b"abcabcabcxyzxyz", 6),
+            [
+                0x78, 0x01, 0x4B, 0x4C, 0x4A, 0x86, 0xA0, 0x8A, 0xCA, 0x2A, 0x20, 0x02, 0x00, 0x2F,
+                0xD8, 0x06, 0x49,
+            ]
+        );
+    }
+
+    #[test]
+    fn apng_bytes() {
+        let frame = Grid::from_vec(2, 1, vec![Rgb(255, 0, 0), Rgb(0, 0, 255)]).unwrap();
+        let mut bytes = vec![];
+        write_apng(&[frame], 1, 10.0, &mut bytes).unwrap();
+
+        let expected: &[&[u8]] = &[
+            b"\x89PNG\r\n\x1a\n",
+            b"\0\0\0\x0dIHDR\0\0\0\x02\0\0\0\x01\x08\x02\0\0\0\x7b\x40\xe8\xdd",
+            b"\0\0\0\x08acTL\0\0\0\x01\0\0\0\0\xb4\x2d\xe9\xa0",
+            b"\0\0\0\x1afcTL\0\0\0\0\0\0\0\x02\0\0\0\x01",
+            b"\0\0\0\0\0\0\0\0\0\x64\x03\xe8\0\0\x10\x28\xe5\x64",
+            b"\0\0\0\x0fIDAT\x78\x01\x63\xf8\xcf\xc0\xc0\xc0\xf0\x1f\0\x07\0\x01\xff",
+            b"\x2c\x33\x12\x05",
+            b"\0\0\0\0IEND\xae\x42\x60\x82",
+        ];
+        assert_eq!(bytes, expected.concat());
+    }
+
+    #[test]
+    fn apng_chunks() {
+        let frames = vec![
+            Grid::filled(3, 2, Rgb::BLUE),
+            Grid::filled(3, 2, Rgb::GREEN),
+        ];
+        let mut bytes = vec![];
+        write_apng(&frames, 2, 10.0, &mut bytes).unwrap();
+
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+
+        let mut kinds = vec![];
+        let mut rest = &bytes[8..];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            kinds.push(String::from_utf8(rest[4..8].to_vec()).unwrap());
+
+            let mut crc = Crc32::new();
+            crc.update(&rest[4..8 + length]);
+            let stored = u32::from_be_bytes(rest[8 + length..12 + length].try_into().unwrap());
+            assert_eq!(crc.finish(), stored);
+
+            rest = &rest[12 + length..];
+        }
+
+        assert_eq!(
+            kinds,
+            ["IHDR", "acTL", "fcTL", "IDAT", "fcTL", "fdAT", "IEND"]
+        );
+    }
+
+    #[test]
+    fn options_round_trip() {
+        let parse = |args: &[&str]| {
+            let args = args.iter().map(|arg| arg.into()).collect();
+            Options::from_args(&mut pico_args::Arguments::from_vec(args))
+        };
+
+        assert_eq!(parse(&["16", "--time"]).unwrap(), None);
+
+        let options = parse(&["--visualize-to", "out", "--fps", "24"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.target, Target::Files("out".into()));
+        assert_eq!(options.fps, 24.0);
+        let args = options.to_args();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        assert_eq!(parse(&args).unwrap(), Some(options));
+
+        let terminal = parse(&["--visualize"]).unwrap().unwrap();
+        assert_eq!(terminal.target, Target::Terminal);
+        assert_eq!(terminal.fps, 10.0);
+
+        for fps in ["x", "0", "-1", "inf"] {
+            assert!(parse(&["--visualize", "--fps", fps]).is_err(), "{fps}");
+        }
+    }
+}